@@ -2,6 +2,7 @@ use super::all::{Clamp, Lerp, Max, Min, Vec4};
 use pyo3::prelude::*;
 
 #[pyclass]
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
 pub struct Color {
     #[pyo3(get, set)]
     pub r: u8,
@@ -47,6 +48,24 @@ impl Lerp<Self, u8> for &Color {
     }
 }
 
+impl Lerp<Self, f64> for &Color {
+    type Output = Color;
+    fn lerp(self, rhs: Self, lerp: f64) -> Self::Output {
+        let channel = |from: u8, to: u8| {
+            (from as f64)
+                .lerp(to as f64, lerp)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Color {
+            r: channel(self.r, rhs.r),
+            g: channel(self.g, rhs.g),
+            b: channel(self.b, rhs.b),
+            a: channel(self.a, rhs.a),
+        }
+    }
+}
+
 #[pymethods]
 impl Color {
     #[classattr]
@@ -108,6 +127,31 @@ impl Color {
     pub fn max_py(&self, rhs: &Self) -> Self {
         self.max(rhs)
     }
+    #[pyo3(name = "lerp")]
+    pub fn lerp_py(&self, rhs: &Self, lerp: f64) -> Self {
+        self.lerp(rhs, lerp)
+    }
+    // Draws `over` on top of this color, mixing them by its alpha
+    pub fn blend(&self, over: Self) -> Self {
+        match over.a {
+            255 => over,
+            0 => *self,
+            _ => {
+                let (over_alpha, alpha) = (over.a as f64 / 255.0, self.a as f64 / 255.0);
+                let total = over_alpha + alpha * (1.0 - over_alpha);
+                let channel = |over: u8, under: u8| {
+                    ((over as f64 * over_alpha + under as f64 * alpha * (1.0 - over_alpha)) / total)
+                        .round() as u8
+                };
+                Self {
+                    r: channel(over.r, self.r),
+                    g: channel(over.g, self.g),
+                    b: channel(over.b, self.b),
+                    a: (total * 255.0).round() as u8,
+                }
+            }
+        }
+    }
     #[staticmethod]
     pub fn from_vec4(vec: Vec4) -> Self {
         Self {
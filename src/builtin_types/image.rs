@@ -0,0 +1,452 @@
+use pyo3::exceptions::{PyIOError, PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use std::{fs::File, io::BufWriter, io::Write};
+
+use super::all::Color;
+
+// RGBA pixel data, used for textures, sprites and screenshots
+#[pyclass]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    #[pyo3(get)]
+    pub(crate) width: usize,
+    #[pyo3(get)]
+    pub(crate) height: usize,
+    pub(crate) pixels: Vec<Color>,
+}
+
+#[pymethods]
+impl Image {
+    #[classattr]
+    pub const INTERPOLATE_NEAREST: u8 = 0;
+    #[classattr]
+    pub const INTERPOLATE_BILINEAR: u8 = 1;
+
+    #[new]
+    #[pyo3(signature = (width, height, color = Color::TRANSPARENT))]
+    pub fn new(width: usize, height: usize, color: Color) -> PyResult<Self> {
+        if width == 0 || height == 0 {
+            return Err(PyValueError::new_err(
+                "An image must be at least 1 pixel wide and tall",
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels: vec![color; Image::pixel_count(width, height)?],
+        })
+    }
+    // Reads a PNG, BMP or PPM file, telling them apart by their contents
+    #[staticmethod]
+    pub fn load(path: &str) -> PyResult<Self> {
+        let bytes = std::fs::read(path)?;
+        let image = if bytes.starts_with(b"\x89PNG") {
+            Image::decode_png(&bytes)
+        } else if bytes.starts_with(b"BM") {
+            Image::decode_bmp(&bytes)
+        } else if bytes.starts_with(b"P6") || bytes.starts_with(b"P3") {
+            Image::decode_ppm(&bytes)
+        } else {
+            Err("unknown format".to_owned())
+        };
+        image.map_err(|err| PyValueError::new_err(format!("Couldn't load '{path}': {err}")))
+    }
+    pub fn get_pixel(&self, x: i64, y: i64) -> PyResult<Color> {
+        Ok(self.pixels[self.index(x, y)?])
+    }
+    pub fn set_pixel(&mut self, x: i64, y: i64, color: Color) -> PyResult<()> {
+        let index = self.index(x, y)?;
+        self.pixels[index] = color;
+        Ok(())
+    }
+    pub fn fill(&mut self, color: Color) {
+        self.pixels.fill(color);
+    }
+    // Draws the source image over this one with its top left corner at (x, y), blending by alpha.
+    // The parts that don't fit are cut off
+    pub fn blit(&mut self, source: &Image, x: i64, y: i64) {
+        for source_y in 0..source.height {
+            for source_x in 0..source.width {
+                let (target_x, target_y) = (
+                    x.saturating_add(source_x as i64),
+                    y.saturating_add(source_y as i64),
+                );
+                if let Some(index) = self.offset(target_x, target_y) {
+                    let color = source.pixels[source_y * source.width + source_x];
+                    self.pixels[index] = self.pixels[index].blend(color);
+                }
+            }
+        }
+    }
+    // Keeps only the given region of the image
+    pub fn crop(&mut self, x: usize, y: usize, width: usize, height: usize) -> PyResult<()> {
+        let fits = |start: usize, size: usize, limit: usize| {
+            size > 0 && start.checked_add(size).map_or(false, |end| end <= limit)
+        };
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return Err(PyValueError::new_err(format!(
+                "Can't crop a {width}x{height} region at ({x}, {y}) from a {0}x{1} image",
+                self.width, self.height
+            )));
+        }
+        self.pixels = (y..y + height)
+            .flat_map(|row| self.pixels[row * self.width + x..][..width].to_vec())
+            .collect();
+        (self.width, self.height) = (width, height);
+        Ok(())
+    }
+    #[pyo3(signature = (width, height, interpolation = Self::INTERPOLATE_BILINEAR))]
+    pub fn resize(&mut self, width: usize, height: usize, interpolation: u8) -> PyResult<()> {
+        if width == 0 || height == 0 {
+            return Err(PyValueError::new_err(
+                "An image must be at least 1 pixel wide and tall",
+            ));
+        }
+        let (scale_x, scale_y) = (
+            self.width as f64 / width as f64,
+            self.height as f64 / height as f64,
+        );
+        let mut pixels = Vec::with_capacity(Image::pixel_count(width, height)?);
+        for y in 0..height {
+            for x in 0..width {
+                // Sampled at the pixel centers, so that shrinking by half picks every other pixel
+                let (source_x, source_y) = ((x as f64 + 0.5) * scale_x, (y as f64 + 0.5) * scale_y);
+                pixels.push(match interpolation {
+                    Self::INTERPOLATE_NEAREST => self.pixel(source_x as usize, source_y as usize),
+                    Self::INTERPOLATE_BILINEAR => self.sample_bilinear(source_x, source_y),
+                    _ => {
+                        return Err(PyValueError::new_err(format!(
+                            "Unknown interpolation {interpolation}"
+                        )))
+                    }
+                });
+            }
+        }
+        (self.width, self.height, self.pixels) = (width, height, pixels);
+        Ok(())
+    }
+    pub fn flip_x(&mut self) {
+        for row in self.pixels.chunks_exact_mut(self.width) {
+            row.reverse();
+        }
+    }
+    pub fn flip_y(&mut self) {
+        let mut rows: Vec<&[Color]> = self.pixels.chunks_exact(self.width).collect();
+        rows.reverse();
+        self.pixels = rows.concat();
+    }
+    pub fn copy(&self) -> Self {
+        self.clone()
+    }
+    // The pixels as RGBA bytes, row by row from the top left
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        PyBytes::new(py, &self.rgba_bytes())
+    }
+    pub fn save_png(&self, path: &str) -> PyResult<()> {
+        let too_large = || PyValueError::new_err("The image is too large to save as a PNG");
+        let (width, height) = (
+            u32::try_from(self.width).map_err(|_| too_large())?,
+            u32::try_from(self.height).map_err(|_| too_large())?,
+        );
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgba_bytes()))
+            .map_err(|err| PyIOError::new_err(format!("Couldn't write '{path}': {err}")))
+    }
+    // 32 bit BMP with an alpha mask, stored bottom row first
+    pub fn save_bmp(&self, path: &str) -> PyResult<()> {
+        const HEADER_SIZE: u32 = 14 + 108;
+        // The header stores the sizes in 32 bits
+        let too_large = || PyValueError::new_err("The image is too large to save as a BMP");
+        let (width, height) = (
+            i32::try_from(self.width).map_err(|_| too_large())?,
+            i32::try_from(self.height).map_err(|_| too_large())?,
+        );
+        let data_size = u32::try_from(self.pixels.len() * 4)
+            .ok()
+            .filter(|size| size.checked_add(HEADER_SIZE).is_some())
+            .ok_or_else(too_large)?;
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(b"BM")?;
+        for value in [HEADER_SIZE + data_size, 0, HEADER_SIZE, 108] {
+            file.write_all(&value.to_le_bytes())?;
+        }
+        file.write_all(&width.to_le_bytes())?;
+        file.write_all(&height.to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?;
+        file.write_all(&32u16.to_le_bytes())?;
+        // Bitfields compression, the image size, the resolution, the palette and the channel masks
+        for value in [
+            3,
+            data_size,
+            2835,
+            2835,
+            0,
+            0,
+            0x00FF0000,
+            0x0000FF00,
+            0x000000FF,
+            0xFF000000,
+            u32::from_le_bytes(*b"BGRs"),
+        ] {
+            file.write_all(&value.to_le_bytes())?;
+        }
+        // The color space endpoints and gamma, unused with sRGB
+        file.write_all(&[0; 48])?;
+        for row in self.pixels.chunks_exact(self.width).rev() {
+            for pixel in row {
+                file.write_all(&[pixel.b, pixel.g, pixel.r, pixel.a])?;
+            }
+        }
+        file.flush()?;
+        Ok(())
+    }
+    // Binary PPM, which has no alpha channel
+    pub fn save_ppm(&self, path: &str) -> PyResult<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        write!(file, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| [pixel.r, pixel.g, pixel.b])
+            .collect();
+        file.write_all(&bytes)?;
+        Ok(())
+    }
+    pub fn __repr__(&self) -> String {
+        format!("Image({0}, {1})", self.width, self.height)
+    }
+}
+
+impl Image {
+    pub fn from_pixels(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        debug_assert_eq!(pixels.len(), width * height);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+    // The number of pixels in an image of the size, if that many can be allocated
+    pub(crate) fn pixel_count(width: usize, height: usize) -> PyResult<usize> {
+        width
+            .checked_mul(height)
+            .filter(|count| {
+                count
+                    .checked_mul(std::mem::size_of::<Color>())
+                    .map_or(false, |size| size <= isize::MAX as usize)
+            })
+            .ok_or_else(|| PyValueError::new_err("The image is too large"))
+    }
+    fn offset(&self, x: i64, y: i64) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+    fn index(&self, x: i64, y: i64) -> PyResult<usize> {
+        self.offset(x, y).ok_or_else(|| {
+            PyIndexError::new_err(format!(
+                "The pixel ({x}, {y}) is outside of the {0}x{1} image",
+                self.width, self.height
+            ))
+        })
+    }
+    // The pixel at the coordinates, clamped to the edges
+    fn pixel(&self, x: usize, y: usize) -> Color {
+        self.pixels[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+    // Mixes the 4 pixels around a point with premultiplied alpha,
+    // so that transparent pixels don't darken their neighbours
+    fn sample_bilinear(&self, x: f64, y: f64) -> Color {
+        let (x, y) = ((x - 0.5).max(0.0), (y - 0.5).max(0.0));
+        let (left, top) = (x.floor() as usize, y.floor() as usize);
+        let (weight_x, weight_y) = (x.fract(), y.fract());
+        let corners = [
+            (self.pixel(left, top), (1.0 - weight_x) * (1.0 - weight_y)),
+            (self.pixel(left + 1, top), weight_x * (1.0 - weight_y)),
+            (self.pixel(left, top + 1), (1.0 - weight_x) * weight_y),
+            (self.pixel(left + 1, top + 1), weight_x * weight_y),
+        ];
+        let alpha: f64 = corners
+            .iter()
+            .map(|(color, weight)| color.a as f64 * weight)
+            .sum();
+        if alpha == 0.0 {
+            return Color::TRANSPARENT;
+        }
+        let channel = |channel: fn(&Color) -> u8| {
+            let sum: f64 = corners
+                .iter()
+                .map(|(color, weight)| channel(color) as f64 * color.a as f64 * weight)
+                .sum();
+            (sum / alpha).round().clamp(0.0, 255.0) as u8
+        };
+        Color {
+            r: channel(|color| color.r),
+            g: channel(|color| color.g),
+            b: channel(|color| color.b),
+            a: alpha.round().clamp(0.0, 255.0) as u8,
+        }
+    }
+    pub fn rgba_bytes(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|pixel| [pixel.r, pixel.g, pixel.b, pixel.a])
+            .collect()
+    }
+    fn decode_png(bytes: &[u8]) -> Result<Self, String> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|err| err.to_string())?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|err| err.to_string())?;
+        let pixels = buffer[..info.buffer_size()]
+            .chunks_exact(info.color_type.samples())
+            .map(|pixel| match *pixel {
+                [l] => Color::new(l, l, l, 255),
+                [l, a] => Color::new(l, l, l, a),
+                [r, g, b] => Color { r, g, b, a: 255 },
+                [r, g, b, a] => Color { r, g, b, a },
+                _ => unreachable!(),
+            })
+            .collect();
+        Ok(Image::from_pixels(
+            info.width as usize,
+            info.height as usize,
+            pixels,
+        ))
+    }
+    // Uncompressed 24 and 32 bit BMPs, stored either way up
+    fn decode_bmp(bytes: &[u8]) -> Result<Self, String> {
+        let read = |offset: usize, size: usize| {
+            bytes
+                .get(offset..offset + size)
+                .map(|bytes| {
+                    bytes
+                        .iter()
+                        .rev()
+                        .fold(0u32, |value, byte| value << 8 | *byte as u32)
+                })
+                .ok_or_else(|| "the file is truncated".to_owned())
+        };
+        let (data_offset, header_size) = (read(10, 4)? as usize, read(14, 4)?);
+        let (width, height) = (read(18, 4)? as i32, read(22, 4)? as i32);
+        let (bits, compression) = (read(28, 2)?, read(30, 4)?);
+        let has_alpha = match (bits, compression) {
+            (24, 0) => false,
+            // Plain 32 bit BMPs usually leave the unused byte at 0
+            (32, 0) => false,
+            (32, 3) if header_size >= 56 => {
+                let masks = [read(54, 4)?, read(58, 4)?, read(62, 4)?];
+                if masks != [0x00FF0000, 0x0000FF00, 0x000000FF] {
+                    return Err("only BGRA channel masks are supported".to_owned());
+                }
+                read(66, 4)? == 0xFF000000
+            }
+            _ => {
+                return Err(format!(
+                    "{bits} bit BMPs with compression {compression} aren't supported"
+                ))
+            }
+        };
+        if width <= 0 || height == 0 {
+            return Err("the image is empty".to_owned());
+        }
+        let (width, rows) = (width as usize, height.unsigned_abs() as usize);
+        let pixel_size = bits as usize / 8;
+        let row_size = (width * pixel_size + 3) & !3;
+        let count =
+            Image::pixel_count(width, rows).map_err(|_| "the image is too large".to_owned())?;
+        let data = bytes
+            .get(data_offset..data_offset.saturating_add(row_size * rows))
+            .ok_or_else(|| "the file is truncated".to_owned())?;
+        let mut pixels = Vec::with_capacity(count);
+        for row in 0..rows {
+            // Positive heights mean the bottom row comes first
+            let row = if height > 0 { rows - 1 - row } else { row };
+            for pixel in data[row * row_size..][..width * pixel_size].chunks_exact(pixel_size) {
+                let a = if has_alpha { pixel[3] } else { 255 };
+                pixels.push(Color {
+                    r: pixel[2],
+                    g: pixel[1],
+                    b: pixel[0],
+                    a,
+                });
+            }
+        }
+        Ok(Image::from_pixels(width, rows, pixels))
+    }
+    // Binary (P6) and text (P3) PPMs with up to 8 bits per channel
+    fn decode_ppm(bytes: &[u8]) -> Result<Self, String> {
+        let mut position = 2;
+        let mut header = [0usize; 3];
+        for value in &mut header {
+            // Whitespace and comments can come between the values
+            loop {
+                match bytes.get(position) {
+                    Some(byte) if byte.is_ascii_whitespace() => position += 1,
+                    Some(b'#') => {
+                        while bytes.get(position).map_or(false, |byte| *byte != b'\n') {
+                            position += 1;
+                        }
+                    }
+                    _ => break,
+                }
+            }
+            let start = position;
+            while bytes.get(position).map_or(false, u8::is_ascii_digit) {
+                position += 1;
+            }
+            *value = std::str::from_utf8(&bytes[start..position])
+                .unwrap()
+                .parse()
+                .map_err(|_| "the header is invalid".to_owned())?;
+        }
+        let [width, height, max] = header;
+        if width == 0 || height == 0 || max == 0 || max > 255 {
+            return Err("only 8 bit PPMs with pixels are supported".to_owned());
+        }
+        let sample_count = Image::pixel_count(width, height)
+            .ok()
+            .and_then(|count| count.checked_mul(3))
+            .ok_or_else(|| "the image is too large".to_owned())?;
+        let samples: Vec<usize> = if bytes.starts_with(b"P6") {
+            // A single whitespace character separates the header from the data
+            bytes
+                .get(position + 1..(position + 1).saturating_add(sample_count))
+                .ok_or_else(|| "the file is truncated".to_owned())?
+                .iter()
+                .map(|byte| *byte as usize)
+                .collect()
+        } else {
+            std::str::from_utf8(&bytes[position..])
+                .map_err(|_| "the data is invalid".to_owned())?
+                .split_ascii_whitespace()
+                .take(sample_count)
+                .map(|sample| sample.parse().map_err(|_| "the data is invalid".to_owned()))
+                .collect::<Result<_, _>>()?
+        };
+        if samples.len() < sample_count {
+            return Err("the file is truncated".to_owned());
+        }
+        let scale = |sample: usize| (sample.min(max) * 255 / max) as u8;
+        let pixels = samples
+            .chunks_exact(3)
+            .map(|pixel| Color {
+                r: scale(pixel[0]),
+                g: scale(pixel[1]),
+                b: scale(pixel[2]),
+                a: 255,
+            })
+            .collect();
+        Ok(Image::from_pixels(width, height, pixels))
+    }
+}
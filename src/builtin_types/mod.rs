@@ -1,15 +1,19 @@
 pub mod color;
+pub mod image;
 pub mod vec2;
 pub mod vec3;
 pub mod vec4;
 pub mod input;
+pub mod transform2d;
 pub mod traits;
 
 pub mod all {
     pub use super::color::*;
+    pub use super::image::*;
     pub use super::vec2::*;
     pub use super::input::*;
     pub use super::traits::*;
+    pub use super::transform2d::*;
     pub use super::vec3::*;
     pub use super::vec4::*;
 }
@@ -0,0 +1,167 @@
+use super::all::{Lerp, Vec2};
+use pyo3::{exceptions::PyValueError, prelude::*};
+use std::f64::consts::{PI, TAU};
+
+// A 2x3 affine transform, made of the x and y basis vectors and the origin.
+// Angles are in radians, like `Node2D.rotation_rad`
+#[pyclass]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform2D {
+    #[pyo3(get, set)]
+    pub x: Vec2,
+    #[pyo3(get, set)]
+    pub y: Vec2,
+    #[pyo3(get, set)]
+    pub origin: Vec2,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Transform2D::IDENTITY
+    }
+}
+
+#[pymethods]
+impl Transform2D {
+    #[classattr]
+    pub const IDENTITY: Self = Self {
+        x: Vec2 { x: 1.0, y: 0.0 },
+        y: Vec2 { x: 0.0, y: 1.0 },
+        origin: Vec2 { x: 0.0, y: 0.0 },
+    };
+
+    #[new]
+    pub const fn new(x: Vec2, y: Vec2, origin: Vec2) -> Self {
+        Self { x, y, origin }
+    }
+    #[staticmethod]
+    #[pyo3(signature = (rotation, position, scale = Vec2::ONE, skew = 0.0))]
+    pub fn from_components(rotation: f64, position: Vec2, scale: Vec2, skew: f64) -> Self {
+        Self {
+            x: Vec2::new(rotation.cos() * scale.x, rotation.sin() * scale.x),
+            y: Vec2::new(
+                -(rotation + skew).sin() * scale.y,
+                (rotation + skew).cos() * scale.y,
+            ),
+            origin: position,
+        }
+    }
+    pub fn get_rotation(&self) -> f64 {
+        self.x.y.atan2(self.x.x)
+    }
+    pub fn get_scale(&self) -> Vec2 {
+        let sign = self.determinant().signum();
+        Vec2::new(self.x.magnitude(), sign * self.y.magnitude())
+    }
+    pub fn get_skew(&self) -> f64 {
+        let sign = self.determinant().signum();
+        let (x, y) = (self.x.normalised(), self.y.normalised() * sign);
+        (x.x * y.x + x.y * y.y).clamp(-1.0, 1.0).acos() - PI / 2.0
+    }
+    pub fn determinant(&self) -> f64 {
+        self.x.x * self.y.y - self.x.y * self.y.x
+    }
+    // Transforms a point, applying the origin
+    pub fn xform(&self, point: Vec2) -> Vec2 {
+        self.basis_xform(point) + self.origin
+    }
+    // Transforms a point by the inverse of the transform
+    pub fn xform_inv(&self, point: Vec2) -> PyResult<Vec2> {
+        Ok(self.affine_inverse()?.xform(point))
+    }
+    // Transforms a direction, ignoring the origin
+    pub fn basis_xform(&self, vector: Vec2) -> Vec2 {
+        self.x * vector.x + self.y * vector.y
+    }
+    pub fn affine_inverse(&self) -> PyResult<Self> {
+        let determinant = self.determinant();
+        if determinant == 0.0 {
+            return Err(PyValueError::new_err(
+                "A transform with a determinant of 0 can't be inverted",
+            ));
+        }
+        let inverse = 1.0 / determinant;
+        let mut transform = Self {
+            x: Vec2::new(self.y.y * inverse, -self.x.y * inverse),
+            y: Vec2::new(-self.y.x * inverse, self.x.x * inverse),
+            origin: Vec2::ZERO,
+        };
+        transform.origin = transform.basis_xform(self.origin) * -1.0;
+        Ok(transform)
+    }
+    // Applies `self` after `rhs`, the same as `self * rhs`
+    pub fn compose(&self, rhs: &Self) -> Self {
+        Self {
+            x: self.basis_xform(rhs.x),
+            y: self.basis_xform(rhs.y),
+            origin: self.xform(rhs.origin),
+        }
+    }
+    pub fn translated(&self, offset: Vec2) -> Self {
+        Self {
+            origin: self.origin + offset,
+            ..*self
+        }
+    }
+    pub fn rotated(&self, rotation: f64) -> Self {
+        Transform2D::from_components(rotation, Vec2::ZERO, Vec2::ONE, 0.0).compose(self)
+    }
+    pub fn scaled(&self, scale: Vec2) -> Self {
+        Self {
+            x: self.x * scale,
+            y: self.y * scale,
+            origin: self.origin * scale,
+        }
+    }
+    // Interpolates the rotation, scale, skew and position separately, turning the shortest way round
+    pub fn interpolate_with(&self, rhs: &Self, weight: f64) -> Self {
+        self.lerp(rhs, weight)
+    }
+    #[pyo3(name = "lerp")]
+    pub fn lerp_py(&self, rhs: &Self, lerp: f64) -> Self {
+        self.lerp(rhs, lerp)
+    }
+    pub fn is_equal_approx(&self, rhs: &Self) -> bool {
+        let close = |a: Vec2, b: Vec2| (a.x - b.x).abs() < 1e-6 && (a.y - b.y).abs() < 1e-6;
+        close(self.x, rhs.x) && close(self.y, rhs.y) && close(self.origin, rhs.origin)
+    }
+    pub fn __mul__(&self, rhs: PyObject, py: Python) -> PyResult<PyObject> {
+        if let Ok(rhs) = rhs.extract::<Transform2D>(py) {
+            return Ok(self.compose(&rhs).into_py(py));
+        } else if let Ok(rhs) = rhs.extract::<Vec2>(py) {
+            return Ok(self.xform(rhs).into_py(py));
+        }
+        Err(PyValueError::new_err(
+            "Only types Transform2D and Vec2 can be multiplied by Transform2D",
+        ))
+    }
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Transform2D({0}, {1}, {2})",
+            self.x.__repr__(),
+            self.y.__repr__(),
+            self.origin.__repr__()
+        )
+    }
+}
+
+impl std::ops::Mul for Transform2D {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.compose(&rhs)
+    }
+}
+
+impl Lerp for &Transform2D {
+    type Output = Transform2D;
+    fn lerp(self, rhs: Self, lerp: f64) -> Self::Output {
+        let from = self.get_rotation();
+        let difference = (rhs.get_rotation() - from + PI).rem_euclid(TAU) - PI;
+        Transform2D::from_components(
+            from + difference * lerp,
+            (&self.origin).lerp(&rhs.origin, lerp),
+            (&self.get_scale()).lerp(&rhs.get_scale(), lerp),
+            self.get_skew().lerp(rhs.get_skew(), lerp),
+        )
+    }
+}
@@ -15,6 +15,8 @@ impl Vec2 {
     #[classattr]
     pub const ZERO: Self = Self { x: 0.0, y: 0.0 };
     #[classattr]
+    pub const ONE: Self = Self { x: 1.0, y: 1.0 };
+    #[classattr]
     pub const UP: Self = Self { x: 0.0, y: 1.0 };
     #[classattr]
     pub const DOWN: Self = Self { x: 0.0, y: -1.0 };
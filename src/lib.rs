@@ -5,16 +5,39 @@ use pyo3::prelude::*;
 
 mod builtin_types;
 mod nodetree;
+mod render;
 mod utils;
 
 use builtin_types::all::*;
-use nodetree::{get_tree, node::builtin_nodes::all::*, NodeTree};
+use nodetree::{
+    awaitable::Awaitable,
+    get_tree,
+    node::{builtin_nodes::all::*, is_instance_valid, signal::Signal},
+    packed_scene::PackedScene,
+    tween::{Tween, Tweener},
+    NodeTree,
+};
+use render::all::*;
 
 #[pymodule]
 fn ferrokrait(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<NodeTree>()?;
     m.add_class::<Node>()?;
+    m.add_class::<Node2D>()?;
+    m.add_class::<Sprite2D>()?;
+    m.add_class::<AnimatedSprite2D>()?;
+    m.add_class::<Timer>()?;
+    m.add_class::<Signal>()?;
+    m.add_class::<PackedScene>()?;
+    m.add_class::<Tween>()?;
+    m.add_class::<Tweener>()?;
+    m.add_class::<Awaitable>()?;
+    m.add_class::<Framebuffer>()?;
+    m.add_class::<Renderer>()?;
+    m.add_class::<TerminalRenderer>()?;
     m.add_class::<Vec2>()?;
+    m.add_class::<Transform2D>()?;
+    m.add_class::<Image>()?;
     m.add_class::<Vec3>()?;
     m.add_class::<Vec4>()?;
     m.add_class::<Input>()?;
@@ -23,5 +46,6 @@ fn ferrokrait(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(lerp_py, m)?)?;
     m.add_function(wrap_pyfunction!(clamp_py, m)?)?;
     m.add_function(wrap_pyfunction!(get_tree, m)?)?;
+    m.add_function(wrap_pyfunction!(is_instance_valid, m)?)?;
     Ok(())
 }
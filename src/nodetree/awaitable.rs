@@ -0,0 +1,80 @@
+use pyo3::prelude::*;
+use pyo3::pyclass::IterNextOutput;
+use pyo3::types::PyTuple;
+use pyo3::{PyTraverseError, PyVisit};
+
+// Returned by `Tree.next_frame`, `Tree.create_timer` and `Node.signal`, and completed by the tree.
+// Awaiting it inside a callback declared with `async def` suspends the callback until then
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct Awaitable {
+    done: bool,
+    value: PyObject,
+    #[pyo3(get)]
+    time_left: f64,
+    // Whether a timer keeps counting down while the tree is paused
+    process_always: bool,
+}
+
+#[pymethods]
+impl Awaitable {
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+    pub fn __await__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+    pub fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+    pub fn __next__(slf: Py<Self>, py: Python) -> IterNextOutput<PyObject, PyObject> {
+        let awaitable = slf.borrow(py);
+        if awaitable.done {
+            IterNextOutput::Return(awaitable.value.clone_ref(py))
+        } else {
+            drop(awaitable);
+            IterNextOutput::Yield(slf.into_py(py))
+        }
+    }
+    // Completes the awaitable, the awaiting coroutine receives None, the only argument or a tuple
+    #[pyo3(signature = (*args))]
+    pub fn __call__(&mut self, py: Python, args: &PyTuple) {
+        let value = match args.len() {
+            0 => py.None(),
+            1 => args.get_item(0).unwrap().into(),
+            _ => args.into(),
+        };
+        self.complete(value);
+    }
+    pub fn __repr__(&self) -> String {
+        format!("Awaitable(done: {})", self.done)
+    }
+    pub fn __traverse__(&self, visit: PyVisit) -> Result<(), PyTraverseError> {
+        visit.call(&self.value)
+    }
+}
+
+impl Awaitable {
+    pub fn new(py: Python, time_left: f64, process_always: bool) -> Self {
+        Self {
+            done: false,
+            value: py.None(),
+            time_left,
+            process_always,
+        }
+    }
+    pub fn complete(&mut self, value: PyObject) {
+        self.done = true;
+        self.value = value;
+    }
+    // Counts a timer down, completing it once it runs out
+    pub fn tick(&mut self, py: Python, delta: f64, paused: bool) {
+        if self.done || (paused && !self.process_always) {
+            return;
+        }
+        self.time_left = (self.time_left - delta).max(0.0);
+        if self.time_left == 0.0 {
+            self.complete(py.None());
+        }
+    }
+}
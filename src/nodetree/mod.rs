@@ -1,57 +1,412 @@
 #![allow(dead_code)]
-use pyo3::{exceptions::PySystemError, prelude::*, types::PyType, PyTraverseError, PyVisit};
+use pyo3::{
+    exceptions::{PyException, PyStopIteration, PySystemError, PyTypeError, PyValueError},
+    prelude::*,
+    types::{PyDict, PyTuple, PyType},
+    PyTraverseError, PyVisit,
+};
 use spin_sleep::LoopHelper;
 
-use std::cell::LazyCell;
-static mut NODETREE: LazyCell<PyObject> =
-    LazyCell::new(|| Python::with_gil(|py| -> PyObject { NodeTree::new().into_py(py) }));
+use std::{cell::RefCell, collections::HashMap};
 
+thread_local! {
+    // Trees that are currently being run or stepped, the innermost being last
+    static ACTIVE_TREES: RefCell<Vec<Py<NodeTree>>> = RefCell::new(Vec::new());
+    // Handed out by `get_tree` when no tree is active, so scripts don't need to create their own
+    static DEFAULT_TREE: RefCell<Option<Py<NodeTree>>> = RefCell::new(None);
+}
+
+// Returns the tree that is currently running or being stepped, or the default tree otherwise
 #[pyfunction]
-pub unsafe fn get_tree<'a>() -> &'a PyObject {
-    &NODETREE
+pub fn get_tree(py: Python) -> PyResult<Py<NodeTree>> {
+    if let Some(tree) =
+        ACTIVE_TREES.with(|trees| trees.borrow().last().map(|tree| tree.clone_ref(py)))
+    {
+        return Ok(tree);
+    }
+    DEFAULT_TREE.with(|tree| {
+        let mut tree = tree.borrow_mut();
+        if tree.is_none() {
+            *tree = Some(Py::new(py, NodeTree::new(py)?)?);
+        }
+        Ok(tree.as_ref().unwrap().clone_ref(py))
+    })
 }
 
+pub mod awaitable;
 pub mod node;
+pub mod packed_scene;
+pub mod tween;
 
-use crate::{builtin_types::all::Input, utils::*};
+use crate::{builtin_types::all::Input, render::all::Renderer};
+use awaitable::Awaitable;
 use node::*;
+use tween::Tween;
 
 #[pyclass(name = "Tree")]
-#[derive(Default, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct NodeTree {
-    nodes: Vec<PyObject>,
+    root: Py<Node>,
+    started: bool,
     running: bool,
+    exit_code: Option<i32>,
+    #[pyo3(get)]
+    paused: bool,
+    groups: HashMap<String, Vec<PyObject>>,
+    process_order: Vec<ProcessEntry>,
+    physics_order: Vec<ProcessEntry>,
+    order_dirty: bool,
+    #[pyo3(get)]
+    frame_count: u64,
+    free_queue: Vec<PyObject>,
+    deferred_calls: Vec<DeferredCall>,
+    tweens: Vec<Py<Tween>>,
+    // Suspended `async def` callbacks, resumed in the order they were started
+    coroutines: Vec<Coroutine>,
+    frame_waiters: Vec<Py<Awaitable>>,
+    timers: Vec<Py<Awaitable>>,
+    // Draws the tree at the end of every frame when set
+    #[pyo3(get, set)]
+    renderer: Option<Py<Renderer>>,
+    #[pyo3(get)]
+    physics_ticks_per_second: u32,
+    #[pyo3(get, set)]
+    max_physics_steps_per_frame: u32,
+    // How far the tree is between the previous physics tick and the next one, from 0 to 1
+    #[pyo3(get)]
+    physics_interpolation_fraction: f64,
+    physics_accumulator: f64,
+    #[pyo3(get)]
+    error_policy: u8,
+    // Called with the node and the exception when the policy is `ERROR_CALLBACK`
+    #[pyo3(get, set)]
+    on_error: Option<PyObject>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProcessEntry {
+    pub node: PyObject,
+    pub mode: u8,
+    pub process_priority: i32,
+    pub physics_priority: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct DeferredCall {
+    pub callable: PyObject,
+    pub args: Py<PyTuple>,
+    // Deferred calls belonging to a node are skipped if it is freed before they run
+    pub target: Option<PyObject>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Coroutine {
+    pub coroutine: PyObject,
+    // Errors are handled on behalf of this node, and the coroutine is dropped once it is freed
+    pub node: Option<PyObject>,
+    pub method: String,
+    pub waiting: Option<Py<Awaitable>>,
 }
 
 #[pymethods]
 impl NodeTree {
-    pub fn add_node(slf: Py<Self>, py: Python, class_object: PyObject) -> PyResult<Py<Self>> {
-        let object: PyObject = class_object.call0(py)?;
-        if object.is_subclass::<Node>(py)? {
-            object.setattr(py, "nodetree_ref", Some(slf.clone_ref(py)))?;
-            let mut bases = object
-                .get_pytype(py)
-                .getattr("__mro__")?
-                .extract::<Vec<Py<PyType>>>()?;
-            bases.remove(bases.len() - 1);
-            bases.remove(bases.len() - 1);
-            bases.reverse();
-            object.setattr(py, "mro", bases)?;
-            slf.borrow_mut(py).nodes.push(object)
-        }
+    #[classattr]
+    pub const ERROR_PROPAGATE: u8 = 0;
+    #[classattr]
+    pub const ERROR_LOG: u8 = 1;
+    #[classattr]
+    pub const ERROR_DISABLE_NODE: u8 = 2;
+    #[classattr]
+    pub const ERROR_CALLBACK: u8 = 3;
+
+    #[new]
+    pub fn py_new(py: Python) -> PyResult<Self> {
+        NodeTree::new(py)
+    }
+    // Adds a node to the root, either an existing instance or one constructed from a class
+    #[pyo3(signature = (node, *args, **kwargs))]
+    pub fn add_node(
+        slf: Py<Self>,
+        py: Python,
+        node: &PyAny,
+        args: &PyTuple,
+        kwargs: Option<&PyDict>,
+    ) -> PyResult<Py<Self>> {
+        let object = if let Ok(class) = node.downcast::<PyType>() {
+            // Checked first, so that other classes are never constructed
+            if !class.is_subclass_of::<Node>()? {
+                return Err(PyTypeError::new_err(format!(
+                    "add_node expects a Node subclass or instance, not the class '{}'",
+                    class.name()?
+                )));
+            }
+            class.call(args, kwargs)?
+        } else if args.is_empty() && kwargs.is_none() {
+            node
+        } else {
+            return Err(PyTypeError::new_err(
+                "Arguments can only be given when adding a node class",
+            ));
+        };
+        let Ok(child) = object.downcast::<PyCell<Node>>() else {
+            return Err(PyTypeError::new_err(format!(
+                "add_node expects a Node subclass or instance, not '{}'",
+                object.get_type().name()?
+            )));
+        };
+        Node::add_child(NodeTree::root(&slf, py), py, child)?;
         Ok(slf)
     }
+    pub fn create_tween(slf: Py<Self>, py: Python) -> PyResult<Py<Tween>> {
+        Tween::create(py, &slf, None)
+    }
+    // Notifies the nodes whose processing is stopped or resumed by the change
+    #[setter]
+    pub fn set_paused(slf: Py<Self>, py: Python, paused: bool) -> PyResult<()> {
+        if slf.borrow(py).paused == paused {
+            return Ok(());
+        }
+        let mut entries = Vec::new();
+        Node::flatten(
+            NodeTree::root(&slf, py),
+            py,
+            Node::PROCESS_MODE_PAUSABLE,
+            &mut entries,
+        )?;
+        slf.borrow_mut(py).paused = paused;
+        for entry in entries {
+            let processing = Node::mode_can_process(entry.mode, paused);
+            if processing == Node::mode_can_process(entry.mode, !paused) {
+                continue;
+            }
+            let what = if processing {
+                Node::NOTIFICATION_UNPAUSED
+            } else {
+                Node::NOTIFICATION_PAUSED
+            };
+            Node::call_chain(as_node(py, &entry.node)?, "_notification", (what,))?;
+        }
+        Ok(())
+    }
+    pub fn propagate_notification(slf: Py<Self>, py: Python, what: i32) -> PyResult<()> {
+        Node::propagate_notification(NodeTree::root(&slf, py), py, what)
+    }
+    // Calls the callable once the current frame's callbacks have run
+    #[pyo3(signature = (callable, *args))]
+    pub fn call_deferred(
+        &mut self,
+        py: Python,
+        callable: PyObject,
+        args: &PyTuple,
+    ) -> PyResult<()> {
+        if !callable.as_ref(py).is_callable() {
+            return Err(PyTypeError::new_err("Only callables can be deferred"));
+        }
+        self.deferred_calls.push(DeferredCall {
+            callable,
+            args: args.into(),
+            target: None,
+        });
+        Ok(())
+    }
+    // Completes at the start of the next frame
+    pub fn next_frame(&mut self, py: Python) -> PyResult<Py<Awaitable>> {
+        let awaitable = Py::new(py, Awaitable::new(py, 0.0, true))?;
+        self.frame_waiters.push(awaitable.clone_ref(py));
+        Ok(awaitable)
+    }
+    #[pyo3(signature = (time_sec, process_always = true))]
+    pub fn create_timer(
+        &mut self,
+        py: Python,
+        time_sec: f64,
+        process_always: bool,
+    ) -> PyResult<Py<Awaitable>> {
+        let mut timer = Awaitable::new(py, time_sec.max(0.0), process_always);
+        if time_sec <= 0.0 {
+            timer.complete(py.None());
+        }
+        let timer = Py::new(py, timer)?;
+        self.timers.push(timer.clone_ref(py));
+        Ok(timer)
+    }
+    #[getter]
+    pub fn get_root(slf: Py<Self>, py: Python) -> Py<Node> {
+        NodeTree::root(&slf, py).into()
+    }
+    pub fn get_node(slf: Py<Self>, py: Python, path: &str) -> PyResult<PyObject> {
+        Node::get_node(NodeTree::root(&slf, py), py, path)
+    }
+    #[pyo3(signature = (pattern, recursive = true))]
+    pub fn find_node(
+        slf: Py<Self>,
+        py: Python,
+        pattern: &str,
+        recursive: bool,
+    ) -> PyResult<Option<PyObject>> {
+        Node::find_node(NodeTree::root(&slf, py), py, pattern, recursive)
+    }
+    pub fn get_nodes_in_group(&self, py: Python, group: &str) -> Vec<PyObject> {
+        self.groups.get(group).map_or_else(Vec::new, |nodes| {
+            nodes.iter().map(|node| node.clone_ref(py)).collect()
+        })
+    }
+    // The nodes are collected first, so that they can use the tree while they're called
+    #[pyo3(signature = (group, method, *args))]
+    pub fn call_group(
+        slf: Py<Self>,
+        py: Python,
+        group: &str,
+        method: &str,
+        args: &PyTuple,
+    ) -> PyResult<()> {
+        let nodes = slf.borrow(py).get_nodes_in_group(py, group);
+        for node in nodes {
+            if !as_node(py, &node)?.borrow().is_freed() {
+                node.call_method1(py, method, args)?;
+            }
+        }
+        Ok(())
+    }
+    pub fn set_group(
+        slf: Py<Self>,
+        py: Python,
+        group: &str,
+        attribute: &str,
+        value: PyObject,
+    ) -> PyResult<()> {
+        let nodes = slf.borrow(py).get_nodes_in_group(py, group);
+        for node in nodes {
+            if !as_node(py, &node)?.borrow().is_freed() {
+                node.setattr(py, attribute, value.clone_ref(py))?;
+            }
+        }
+        Ok(())
+    }
+    #[setter]
+    pub fn set_error_policy(&mut self, policy: u8) -> PyResult<()> {
+        if policy > NodeTree::ERROR_CALLBACK {
+            return Err(PyValueError::new_err(format!(
+                "{policy} is not a valid error policy"
+            )));
+        }
+        self.error_policy = policy;
+        Ok(())
+    }
+    #[setter]
+    pub fn set_physics_ticks_per_second(&mut self, ticks: u32) -> PyResult<()> {
+        if ticks == 0 {
+            return Err(PyValueError::new_err(
+                "physics_ticks_per_second must be greater than 0",
+            ));
+        }
+        self.physics_ticks_per_second = ticks;
+        Ok(())
+    }
     /// ====> _|_|_|_ -----|-----
-    pub fn run(slf: Py<Self>, py: Python, fps: Option<usize>) -> PyResult<()> {
+    #[pyo3(signature = (fps = None, max_frames = None))]
+    pub fn run(
+        slf: Py<Self>,
+        py: Python,
+        fps: Option<usize>,
+        max_frames: Option<u64>,
+    ) -> PyResult<i32> {
         if slf.borrow(py).running {
             return Err(PySystemError::new_err("This NodeTree is already running!"));
         }
-        slf.borrow_mut(py).running = true;
-        let nodes: &Vec<PyObject> = &slf.borrow(py).nodes;
+        {
+            let mut tree = slf.borrow_mut(py);
+            tree.running = true;
+            tree.exit_code = None;
+        }
+        let result = NodeTree::activate(&slf, py, || NodeTree::run_loop(&slf, py, fps, max_frames));
+        let mut tree = slf.borrow_mut(py);
+        tree.running = false;
+        result?;
+        Ok(tree.exit_code.take().unwrap_or(0))
+    }
+    // Advances the tree by exactly one frame, without sleeping or polling input
+    pub fn step(slf: Py<Self>, py: Python, delta: f64) -> PyResult<()> {
+        NodeTree::step_frames(slf, py, 1, delta)
+    }
+    pub fn step_frames(slf: Py<Self>, py: Python, frames: u64, delta: f64) -> PyResult<()> {
+        if slf.borrow(py).running {
+            return Err(PySystemError::new_err(
+                "A running NodeTree can't be stepped manually!",
+            ));
+        }
+        NodeTree::activate(&slf, py, || {
+            NodeTree::start(&slf, py)?;
+            for _ in 0..frames {
+                NodeTree::frame(&slf, py, delta, false)?;
+            }
+            Ok(())
+        })
+    }
+    // Makes `run` return the given exit code once the current frame has finished
+    #[pyo3(signature = (exit_code = 0))]
+    pub fn quit(&mut self, exit_code: i32) {
+        self.exit_code = Some(exit_code);
+    }
+    pub fn __repr__(&self) -> String {
+        format!("{self:#?}")
+    }
+    pub fn __traverse__(&self, visit: PyVisit) -> Result<(), PyTraverseError> {
+        visit.call(&self.root)?;
+        for entry in self.process_order.iter().chain(&self.physics_order) {
+            visit.call(&entry.node)?;
+        }
+        for node in self.groups.values().flatten() {
+            visit.call(node)?;
+        }
+        for tween in &self.tweens {
+            visit.call(tween)?;
+        }
+        for coroutine in &self.coroutines {
+            visit.call(&coroutine.coroutine)?;
+            if let Some(node) = &coroutine.node {
+                visit.call(node)?;
+            }
+            if let Some(waiting) = &coroutine.waiting {
+                visit.call(waiting)?;
+            }
+        }
+        for awaitable in self.frame_waiters.iter().chain(&self.timers) {
+            visit.call(awaitable)?;
+        }
+        if let Some(renderer) = &self.renderer {
+            visit.call(renderer)?;
+        }
+        if let Some(on_error) = &self.on_error {
+            visit.call(on_error)?;
+        }
+        for call in &self.deferred_calls {
+            visit.call(&call.callable)?;
+            visit.call(&call.args)?;
+        }
+        self.free_queue
+            .iter()
+            .try_for_each(|py_object| -> Result<(), PyTraverseError> { visit.call(py_object) })
+    }
+}
 
-        for node in nodes {
-            node.call_method0(py, "_ready_recursive")?;
+impl NodeTree {
+    // The root node is created before the tree is moved onto the python heap,
+    // so it only receives its reference to the tree once it is first needed
+    pub fn root<'py>(slf: &Py<Self>, py: Python<'py>) -> &'py PyCell<Node> {
+        let root: &PyCell<Node> = slf.borrow(py).root.clone_ref(py).into_ref(py);
+        if root.borrow().tree.is_none() {
+            root.borrow_mut().tree = Some(slf.clone_ref(py));
         }
+        root
+    }
+    fn run_loop(
+        slf: &Py<Self>,
+        py: Python,
+        fps: Option<usize>,
+        max_frames: Option<u64>,
+    ) -> PyResult<()> {
+        NodeTree::start(slf, py)?;
 
         let mut loop_helper: LoopHelper = if let Some(fps) = fps {
             LoopHelper::builder()
@@ -63,41 +418,393 @@ impl NodeTree {
                 .build_without_target_rate()
         };
 
-        loop {
+        let mut frames: u64 = 0;
+        while max_frames.map_or(true, |max_frames| frames < max_frames)
+            && slf.borrow(py).exit_code.is_none()
+        {
             let delta: f64 = loop_helper.loop_start_s();
             py.check_signals()?;
-            if unsafe { !Input::all_keys().is_empty() } {
-                for node in nodes {
-                    node.call_method0(py, "_on_key_input_recursive")?;
+            NodeTree::frame(slf, py, delta, true)?;
+            frames += 1;
+            loop_helper.loop_sleep();
+        }
+        Ok(())
+    }
+    // Decides what happens to an exception raised by one of a node's callbacks
+    pub fn handle_error(
+        slf: &Py<Self>,
+        py: Python,
+        node: &PyCell<Node>,
+        method: &str,
+        err: PyErr,
+    ) -> PyResult<()> {
+        // Interrupts and exits must always reach the user
+        if !err.is_instance_of::<PyException>(py) {
+            return Err(err);
+        }
+        // Failing to find the node mustn't replace the user's error, so fall back to its name
+        let location = match Node::get_path(node, py) {
+            Ok(path) => format!("at '{path}'"),
+            Err(_) => format!("called '{0}'", node.borrow().name),
+        };
+        let context = format!("in {method} of the node {location}");
+        // Exception notes only exist from python 3.11 onwards, so failing to add one is fine
+        let _ = err
+            .value(py)
+            .call_method1("add_note", (format!("(raised {context})"),));
+        let (policy, on_error) = {
+            let tree = slf.borrow(py);
+            (tree.error_policy, tree.on_error.clone())
+        };
+        match (policy, on_error) {
+            (NodeTree::ERROR_LOG, _) => {
+                eprintln!("Error {context}:");
+                err.print(py);
+                Ok(())
+            }
+            (NodeTree::ERROR_DISABLE_NODE, _) => {
+                eprintln!("Error {context}, the node has been disabled:");
+                err.print(py);
+                node.borrow_mut().disable(py);
+                Ok(())
+            }
+            (NodeTree::ERROR_CALLBACK, Some(on_error)) => {
+                on_error.call1(py, (node, err.value(py)))?;
+                Ok(())
+            }
+            _ => Err(err),
+        }
+    }
+    // Makes the tree the one returned by `get_tree` for as long as `f` runs
+    fn activate<R>(slf: &Py<Self>, py: Python, f: impl FnOnce() -> R) -> R {
+        ACTIVE_TREES.with(|trees| trees.borrow_mut().push(slf.clone_ref(py)));
+        let result = f();
+        ACTIVE_TREES.with(|trees| trees.borrow_mut().pop());
+        result
+    }
+    // Readies every node in the tree the first time it is run or stepped
+    fn start(slf: &Py<Self>, py: Python) -> PyResult<()> {
+        if slf.borrow(py).started {
+            return Ok(());
+        }
+        slf.borrow_mut(py).started = true;
+        Node::_ready_recursive(NodeTree::root(slf, py), py)
+    }
+    fn frame(slf: &Py<Self>, py: Python, delta: f64, poll_input: bool) -> PyResult<()> {
+        let root = NodeTree::root(slf, py);
+        NodeTree::advance_awaitables(slf, py, delta);
+        if poll_input && unsafe { !Input::all_keys().is_empty() } {
+            Node::_on_key_input_recursive(root, py)?;
+        }
+        NodeTree::physics_frame(slf, py, delta)?;
+        NodeTree::process_nodes(slf, py, delta, false)?;
+        NodeTree::process_tweens(slf, py, delta)?;
+        NodeTree::resume_coroutines(slf, py)?;
+        NodeTree::flush_deferred_calls(slf, py)?;
+        NodeTree::flush_free_queue(slf, py)?;
+        NodeTree::render(slf, py)?;
+        slf.borrow_mut(py).frame_count += 1;
+        Ok(())
+    }
+    pub fn register_group(&mut self, group: &str, node: PyObject) {
+        self.groups.entry(group.to_owned()).or_default().push(node);
+    }
+    pub fn unregister_group(&mut self, py: Python, group: &str, node: &PyCell<Node>) {
+        if let Some(nodes) = self.groups.get_mut(group) {
+            nodes.retain(|other| !other.as_ref(py).is(node));
+            if nodes.is_empty() {
+                self.groups.remove(group);
+            }
+        }
+    }
+    // Rebuilds the update order after nodes have been added, removed or reprioritised.
+    // Sorting is stable, so nodes with equal priorities keep their order in the tree
+    fn update_process_order(slf: &Py<Self>, py: Python) -> PyResult<()> {
+        if !slf.borrow(py).order_dirty {
+            return Ok(());
+        }
+        let mut entries = Vec::new();
+        Node::flatten(
+            NodeTree::root(slf, py),
+            py,
+            Node::PROCESS_MODE_PAUSABLE,
+            &mut entries,
+        )?;
+        let mut tree = slf.borrow_mut(py);
+        tree.physics_order = entries.clone();
+        tree.physics_order
+            .sort_by_key(|entry| entry.physics_priority);
+        tree.process_order = entries;
+        tree.process_order
+            .sort_by_key(|entry| entry.process_priority);
+        tree.order_dirty = false;
+        Ok(())
+    }
+    fn process_nodes(slf: &Py<Self>, py: Python, delta: f64, physics: bool) -> PyResult<()> {
+        NodeTree::update_process_order(slf, py)?;
+        let (entries, paused) = {
+            let tree = slf.borrow(py);
+            let entries = if physics {
+                &tree.physics_order
+            } else {
+                &tree.process_order
+            };
+            (entries.clone(), tree.paused)
+        };
+        let method = if physics {
+            "_physics_process"
+        } else {
+            "_process"
+        };
+        for entry in entries {
+            let node = as_node(py, &entry.node)?;
+            // Nodes removed during this frame are skipped, and nodes added are picked up next frame
+            if node.borrow().tree.is_none() || !Node::mode_can_process(entry.mode, paused) {
+                continue;
+            }
+            builtin_nodes::internal_process(node, py, delta, physics)?;
+            Node::call_chain(node, method, (delta,))?;
+        }
+        Ok(())
+    }
+    // Runs as many fixed physics ticks as the elapsed time allows, capped so that a slow
+    // frame can't cause an ever growing backlog of ticks
+    fn physics_frame(slf: &Py<Self>, py: Python, delta: f64) -> PyResult<()> {
+        let (step, max_steps) = {
+            let tree = slf.borrow(py);
+            (
+                1.0 / tree.physics_ticks_per_second as f64,
+                tree.max_physics_steps_per_frame,
+            )
+        };
+        slf.borrow_mut(py).physics_accumulator += delta;
+        let mut steps = 0;
+        while steps < max_steps && slf.borrow(py).physics_accumulator >= step {
+            NodeTree::process_nodes(slf, py, step, true)?;
+            slf.borrow_mut(py).physics_accumulator -= step;
+            steps += 1;
+        }
+        let mut tree = slf.borrow_mut(py);
+        tree.physics_accumulator %= step;
+        tree.physics_interpolation_fraction = tree.physics_accumulator / step;
+        Ok(())
+    }
+    // Steps every tween, dropping the ones that finished or were killed
+    fn process_tweens(slf: &Py<Self>, py: Python, delta: f64) -> PyResult<()> {
+        let tweens = slf.borrow(py).tweens.clone();
+        let paused = slf.borrow(py).paused;
+        let mut finished = Vec::new();
+        for tween in tweens {
+            let cell = tween.as_ref(py);
+            // Tweens bound to a node follow its process mode instead
+            if paused && cell.borrow().bound_node.is_none() {
+                continue;
+            }
+            if !Tween::step(cell, py, delta)? {
+                finished.push(tween);
+            }
+        }
+        slf.borrow_mut(py)
+            .tweens
+            .retain(|tween| !finished.iter().any(|other| other.is(tween)));
+        Ok(())
+    }
+    // Completes the frame waiters from the previous frame and counts the timers down
+    fn advance_awaitables(slf: &Py<Self>, py: Python, delta: f64) {
+        let mut tree = slf.borrow_mut(py);
+        for waiter in std::mem::take(&mut tree.frame_waiters) {
+            waiter.borrow_mut(py).complete(py.None());
+        }
+        let paused = tree.paused;
+        tree.timers.retain(|timer| {
+            let mut timer = timer.borrow_mut(py);
+            timer.tick(py, delta, paused);
+            !timer.is_done()
+        });
+    }
+    // Runs callbacks declared with `async def` until their first await
+    pub fn start_coroutine(
+        slf: &Py<Self>,
+        py: Python,
+        result: &PyAny,
+        node: Option<PyObject>,
+        method: &str,
+    ) -> PyResult<()> {
+        if result.is_none() || !result.hasattr("__await__")? || !result.hasattr("send")? {
+            return Ok(());
+        }
+        let coroutine = Coroutine {
+            coroutine: result.into(),
+            node,
+            method: method.to_owned(),
+            waiting: None,
+        };
+        if let Some(coroutine) = NodeTree::resume(slf, py, coroutine)? {
+            slf.borrow_mut(py).coroutines.push(coroutine);
+        }
+        Ok(())
+    }
+    // Runs the coroutine until its next await, returning it if it hasn't finished
+    fn resume(slf: &Py<Self>, py: Python, mut coroutine: Coroutine) -> PyResult<Option<Coroutine>> {
+        let result = coroutine
+            .coroutine
+            .call_method1(py, "send", (py.None(),))
+            .and_then(|awaited| {
+                awaited.extract::<Py<Awaitable>>(py).map_err(|_| {
+                    let _ = coroutine.coroutine.call_method0(py, "close");
+                    PyTypeError::new_err(
+                        "Coroutines run by the tree can only await frames, timers and signals",
+                    )
+                })
+            });
+        match result {
+            Ok(awaitable) => {
+                coroutine.waiting = Some(awaitable);
+                Ok(Some(coroutine))
+            }
+            Err(err) if err.is_instance_of::<PyStopIteration>(py) => Ok(None),
+            Err(err) => {
+                match &coroutine.node {
+                    Some(node) => {
+                        NodeTree::handle_error(slf, py, as_node(py, node)?, &coroutine.method, err)?
+                    }
+                    None => return Err(err),
                 }
+                Ok(None)
             }
-            for node in nodes {
-                node.call_method1(py, "_process_recursive", (delta,))?;
+        }
+    }
+    // Resumes the coroutine if its awaitable has completed, returning it if it should be kept
+    // along with whether it was resumed. Coroutines of freed nodes are closed instead
+    fn resume_if_ready(
+        slf: &Py<Self>,
+        py: Python,
+        coroutine: Coroutine,
+    ) -> PyResult<(Option<Coroutine>, bool)> {
+        if let Some(node) = &coroutine.node {
+            let node = as_node(py, node)?;
+            if node.borrow().is_freed() {
+                if let Err(err) = coroutine.coroutine.call_method0(py, "close") {
+                    NodeTree::handle_error(slf, py, node, &coroutine.method, err)?;
+                }
+                return Ok((None, false));
             }
-            loop_helper.loop_sleep();
+            if !Node::can_process(node, py)? {
+                return Ok((Some(coroutine), false));
+            }
+        }
+        let ready = coroutine
+            .waiting
+            .as_ref()
+            .map_or(true, |awaitable| awaitable.borrow(py).is_done());
+        if !ready {
+            return Ok((Some(coroutine), false));
         }
+        Ok((NodeTree::resume(slf, py, coroutine)?, true))
     }
-    pub fn __repr__(&self) -> String {
-        format!("{self:#?}")
+    // Resumes every coroutine whose awaitable has completed, in the order they were started.
+    // Repeats while any were resumed, as they may have emitted signals others are waiting on
+    fn resume_coroutines(slf: &Py<Self>, py: Python) -> PyResult<()> {
+        loop {
+            let coroutines = std::mem::take(&mut slf.borrow_mut(py).coroutines);
+            let mut kept = Vec::new();
+            let mut resumed = false;
+            let mut result = Ok(());
+            let mut coroutines = coroutines.into_iter();
+            while let Some(coroutine) = coroutines.next() {
+                match NodeTree::resume_if_ready(slf, py, coroutine) {
+                    Ok((coroutine, ran)) => {
+                        kept.extend(coroutine);
+                        resumed |= ran;
+                    }
+                    // Keep the rest running so that a single error doesn't lose them
+                    Err(err) => {
+                        kept.extend(coroutines.by_ref());
+                        result = Err(err);
+                    }
+                }
+            }
+            // Coroutines started while resuming go after the ones that were already running
+            let mut tree = slf.borrow_mut(py);
+            let started = std::mem::replace(&mut tree.coroutines, kept);
+            tree.coroutines.extend(started);
+            if result.is_err() || !resumed {
+                return result;
+            }
+        }
     }
-    pub fn __traverse__(&self, visit: PyVisit) -> Result<(), PyTraverseError> {
-        self.nodes
-            .iter()
-            .try_for_each(|py_object| -> Result<(), PyTraverseError> { visit.call(py_object) })
+    fn render(slf: &Py<Self>, py: Python) -> PyResult<()> {
+        let renderer = slf.borrow(py).renderer.clone();
+        if let Some(renderer) = renderer {
+            // Called through Python so that subclasses can present the frame
+            renderer.call_method1(py, "render", (NodeTree::root(slf, py),))?;
+        }
+        Ok(())
     }
-    pub fn __clear__(&mut self) {
-        self.nodes.clear()
+    // Runs queued calls in the order they were made, including any queued while flushing
+    fn flush_deferred_calls(slf: &Py<Self>, py: Python) -> PyResult<()> {
+        loop {
+            let calls = std::mem::take(&mut slf.borrow_mut(py).deferred_calls);
+            if calls.is_empty() {
+                return Ok(());
+            }
+            for call in calls {
+                if let Some(target) = &call.target {
+                    if as_node(py, target)?.borrow().is_freed() {
+                        continue;
+                    }
+                }
+                let result = call.callable.call1(py, call.args.as_ref(py))?;
+                NodeTree::start_coroutine(
+                    slf,
+                    py,
+                    result.as_ref(py),
+                    call.target,
+                    "a deferred call",
+                )?;
+            }
+        }
     }
-}
-
-impl NodeTree {
-    pub fn get_from_id(&self, id: usize) -> Option<&PyObject> {
-        self.nodes.get(id)
+    // Frees every node that called `queue_free` during the frame
+    fn flush_free_queue(slf: &Py<Self>, py: Python) -> PyResult<()> {
+        loop {
+            let queue = std::mem::take(&mut slf.borrow_mut(py).free_queue);
+            if queue.is_empty() {
+                return Ok(());
+            }
+            for node in queue {
+                let node = as_node(py, &node)?;
+                if !node.borrow().is_freed() {
+                    Node::free(node, py)?;
+                }
+            }
+        }
     }
-    pub const fn new() -> Self {
-        Self {
-            nodes: Vec::new(),
+    pub fn new(py: Python) -> PyResult<Self> {
+        Ok(Self {
+            root: Py::new(py, Node::named("root"))?,
+            started: false,
             running: false,
-        }
+            exit_code: None,
+            paused: false,
+            groups: HashMap::new(),
+            process_order: Vec::new(),
+            physics_order: Vec::new(),
+            order_dirty: true,
+            frame_count: 0,
+            free_queue: Vec::new(),
+            deferred_calls: Vec::new(),
+            tweens: Vec::new(),
+            coroutines: Vec::new(),
+            frame_waiters: Vec::new(),
+            timers: Vec::new(),
+            renderer: None,
+            physics_ticks_per_second: 60,
+            max_physics_steps_per_frame: 8,
+            physics_interpolation_fraction: 0.0,
+            physics_accumulator: 0.0,
+            error_policy: NodeTree::ERROR_PROPAGATE,
+            on_error: None,
+        })
     }
 }
@@ -1,40 +1,978 @@
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+use pyo3::types::{PyDict, PyTuple, PyType};
+use pyo3::{
+    exceptions::{PyLookupError, PyReferenceError, PyTypeError, PyValueError},
+    PyTraverseError, PyVisit,
+};
+use std::collections::HashMap;
+
+use super::{
+    awaitable::Awaitable, packed_scene::register_class, tween::Tween, DeferredCall, NodeTree,
+    ProcessEntry,
+};
+use crate::utils::*;
 
 pub mod builtin_nodes;
+pub mod signal;
+
+use signal::*;
 
 #[pyclass(subclass)]
 #[derive(Clone, Default, Debug)]
 pub struct Node {
     #[pyo3(set, get)]
     mro: Vec<Py<PyType>>,
+    // Set when the class opts out of chaining with `__ferrokrait_chain__ = False`
+    override_callbacks: bool,
+    pub(crate) name: String,
+    parent: Option<PyObject>,
+    children: Vec<PyObject>,
+    signals: HashMap<String, SignalSlot>,
+    groups: Vec<String>,
+    pub(crate) tree: Option<Py<NodeTree>>,
+    #[pyo3(get)]
+    process_mode: u8,
+    #[pyo3(get)]
+    process_priority: i32,
+    #[pyo3(get)]
+    physics_priority: i32,
+    is_ready: bool,
+    queued_for_deletion: bool,
+    freed: bool,
 }
 
 #[pymethods]
 impl Node {
+    #[classattr]
+    pub const CONNECT_DEFERRED: u8 = 1;
+    #[classattr]
+    pub const CONNECT_ONE_SHOT: u8 = 4;
+    #[classattr]
+    pub const PROCESS_MODE_INHERIT: u8 = 0;
+    #[classattr]
+    pub const PROCESS_MODE_PAUSABLE: u8 = 1;
+    #[classattr]
+    pub const PROCESS_MODE_WHEN_PAUSED: u8 = 2;
+    #[classattr]
+    pub const PROCESS_MODE_ALWAYS: u8 = 3;
+    #[classattr]
+    pub const PROCESS_MODE_DISABLED: u8 = 4;
+    #[classattr]
+    pub const NOTIFICATION_PREDELETE: i32 = 1;
+    #[classattr]
+    pub const NOTIFICATION_ENTER_TREE: i32 = 10;
+    #[classattr]
+    pub const NOTIFICATION_EXIT_TREE: i32 = 11;
+    #[classattr]
+    pub const NOTIFICATION_READY: i32 = 13;
+    #[classattr]
+    pub const NOTIFICATION_PAUSED: i32 = 14;
+    #[classattr]
+    pub const NOTIFICATION_UNPAUSED: i32 = 15;
+    #[classattr]
+    pub const NOTIFICATION_PARENTED: i32 = 18;
+    #[classattr]
+    pub const NOTIFICATION_UNPARENTED: i32 = 19;
+    #[classattr]
+    pub const NOTIFICATION_WM_FOCUS_IN: i32 = 1004;
+    #[classattr]
+    pub const NOTIFICATION_WM_FOCUS_OUT: i32 = 1005;
+    // Properties that are saved when the node is packed into a scene
+    #[classattr]
+    #[pyo3(name = "__export__")]
+    pub fn export() -> Vec<&'static str> {
+        vec!["process_mode", "process_priority", "physics_priority"]
+    }
+    // Whether callbacks are called on every class in the mro, subclasses can set this to
+    // False so that callbacks override each other like normal methods
+    #[classattr]
+    #[pyo3(name = "__ferrokrait_chain__")]
+    pub const fn chain() -> bool {
+        true
+    }
+
     #[new]
-    pub fn new() -> Self {
-        Self { mro: Vec::new() }
+    pub fn py_new() -> Self {
+        Self::new()
     }
+    // Lets scenes create instances of every node class defined in python, and lets the class
+    // define `__init__` with its own parameters
+    #[classmethod]
+    pub fn __init_subclass__(cls: &PyType, py: Python) -> PyResult<()> {
+        register_class(cls);
+        if !cls.getattr("__dict__")?.contains("__new__")? {
+            cls.setattr("__new__", wrap_pyfunction!(new_subclass, py)?)?;
+        }
+        Ok(())
+    }
+    pub const fn _enter_tree(&self) {}
     pub const fn _ready(&self) {}
     pub const fn _process(&self, _delta: f64) {}
+    pub const fn _physics_process(&self, _delta: f64) {}
     pub const fn _on_key_input(&self) {}
+    pub const fn _exit_tree(&self) {}
+    pub const fn _notification(&self, _what: i32) {}
+    pub fn add_child(slf: &PyCell<Self>, py: Python, child: &PyCell<Node>) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        child.borrow().ensure_valid()?;
+        if child.borrow().parent.is_some() {
+            return Err(PyValueError::new_err("This node already has a parent!"));
+        }
+        if child.is(slf) || Node::is_ancestor_of(child, py, slf)? {
+            return Err(PyValueError::new_err(
+                "A node can't be added as a child of itself or one of its children!",
+            ));
+        }
+        if child.borrow().name.is_empty() {
+            child.borrow_mut().name = child.get_pytype(py).name()?.to_owned();
+        }
+        Node::make_name_unique(slf, py, child)?;
+        child.borrow_mut().parent = Some(slf.into());
+        slf.borrow_mut().children.push(child.into());
+        Node::cache_mro(child, py)?;
+        Node::call_chain(child, "_notification", (Node::NOTIFICATION_PARENTED,))?;
+        let tree = slf.borrow().tree.clone();
+        if let Some(tree) = tree {
+            Node::enter_tree(child, py, &tree)?;
+            if tree.borrow(py).started {
+                Node::_ready_recursive(child, py)?;
+            }
+        }
+        Ok(())
+    }
+    pub fn remove_child(slf: &PyCell<Self>, py: Python, child: &PyCell<Node>) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        child.borrow().ensure_valid()?;
+        let index = slf
+            .borrow()
+            .children
+            .iter()
+            .position(|node| node.is(child))
+            .ok_or_else(|| PyValueError::new_err("The given node is not a child of this node!"))?;
+        Node::exit_tree(child, py)?;
+        slf.borrow_mut().children.remove(index);
+        child.borrow_mut().parent = None;
+        Node::call_chain(child, "_notification", (Node::NOTIFICATION_UNPARENTED,))
+    }
+    pub fn get_tree(&self) -> PyResult<Option<Py<NodeTree>>> {
+        self.ensure_valid()?;
+        Ok(self.tree.clone())
+    }
+    // Creates a tween that stops when this node is freed and pauses when it can't process
+    pub fn create_tween(slf: &PyCell<Self>, py: Python) -> PyResult<Py<Tween>> {
+        slf.borrow().ensure_valid()?;
+        let tree = slf.borrow().tree.clone();
+        let Some(tree) = tree else {
+            return Err(PyValueError::new_err(
+                "Only nodes inside a tree can create tweens!",
+            ));
+        };
+        Tween::create(py, &tree, Some(slf.into()))
+    }
+    pub fn get_parent(&self) -> PyResult<Option<PyObject>> {
+        self.ensure_valid()?;
+        Ok(self.parent.clone())
+    }
+    pub fn get_children(&self) -> PyResult<Vec<PyObject>> {
+        self.ensure_valid()?;
+        Ok(self.children.clone())
+    }
+    #[getter]
+    pub fn get_name(&self) -> PyResult<String> {
+        self.ensure_valid()?;
+        Ok(self.name.clone())
+    }
+    #[setter]
+    pub fn set_name(slf: &PyCell<Self>, py: Python, name: String) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        if name.is_empty() || name.contains('/') || name == "." || name == ".." {
+            return Err(PyValueError::new_err(format!(
+                "'{name}' is not a valid node name!"
+            )));
+        }
+        slf.borrow_mut().name = name;
+        let parent = slf.borrow().parent.clone();
+        if let Some(parent) = parent {
+            Node::make_name_unique(as_node(py, &parent)?, py, slf)?;
+        }
+        Ok(())
+    }
+    pub fn get_path(slf: &PyCell<Self>, py: Python) -> PyResult<String> {
+        slf.borrow().ensure_valid()?;
+        let mut names = vec![slf.borrow().name.clone()];
+        let mut parent = slf.borrow().parent.clone();
+        while let Some(current) = parent {
+            let node = as_node(py, &current)?.borrow();
+            names.push(node.name.clone());
+            parent = node.parent.clone();
+        }
+        names.reverse();
+        let path = names.join("/");
+        Ok(if slf.borrow().tree.is_some() {
+            format!("/{path}")
+        } else {
+            path
+        })
+    }
+    pub fn get_node(slf: &PyCell<Self>, py: Python, path: &str) -> PyResult<PyObject> {
+        Node::get_node_or_none(slf, py, path)?.ok_or_else(|| {
+            PyLookupError::new_err(format!("No node could be found at the path '{path}'"))
+        })
+    }
+    pub fn get_node_or_none(
+        slf: &PyCell<Self>,
+        py: Python,
+        path: &str,
+    ) -> PyResult<Option<PyObject>> {
+        slf.borrow().ensure_valid()?;
+        let mut current: PyObject = slf.into();
+        let mut parts = path.split('/');
+        if path.starts_with('/') {
+            let Some(tree) = slf.borrow().tree.clone() else {
+                return Ok(None);
+            };
+            let root = NodeTree::root(&tree, py);
+            parts.next();
+            match parts.next() {
+                Some(name) if name == root.borrow().name => current = root.into(),
+                _ => return Ok(None),
+            }
+        }
+        for part in parts {
+            let node = as_node(py, &current)?;
+            let next = match part {
+                "" | "." => Some(current.clone_ref(py)),
+                ".." => node.borrow().parent.clone(),
+                name => Node::get_child_by_name(node, py, name)?,
+            };
+            match next {
+                Some(next) => current = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(current))
+    }
+    #[pyo3(signature = (pattern, recursive = true))]
+    pub fn find_node(
+        slf: &PyCell<Self>,
+        py: Python,
+        pattern: &str,
+        recursive: bool,
+    ) -> PyResult<Option<PyObject>> {
+        slf.borrow().ensure_valid()?;
+        let children = slf.borrow().children.clone();
+        for child in &children {
+            if wildcard_match(pattern, &as_node(py, child)?.borrow().name) {
+                return Ok(Some(child.clone_ref(py)));
+            }
+        }
+        if recursive {
+            for child in &children {
+                if let Some(found) = Node::find_node(as_node(py, child)?, py, pattern, true)? {
+                    return Ok(Some(found));
+                }
+            }
+        }
+        Ok(None)
+    }
+    pub fn queue_free(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        if slf.borrow().queued_for_deletion {
+            return Ok(());
+        }
+        let tree = slf.borrow().tree.clone();
+        if let Some(tree) = tree {
+            if slf.borrow().parent.is_none() {
+                return Err(PyValueError::new_err("The root node can't be freed!"));
+            }
+            Node::mark_queued(slf, py)?;
+            tree.borrow_mut(py).free_queue.push(slf.into());
+            Ok(())
+        } else {
+            Node::free(slf, py)
+        }
+    }
+    pub const fn is_queued_for_deletion(&self) -> bool {
+        self.queued_for_deletion
+    }
+    pub fn add_to_group(slf: &PyCell<Self>, py: Python, group: String) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        if slf.borrow().groups.contains(&group) {
+            return Ok(());
+        }
+        if let Some(tree) = &slf.borrow().tree {
+            tree.borrow_mut(py).register_group(&group, slf.into());
+        }
+        slf.borrow_mut().groups.push(group);
+        Ok(())
+    }
+    pub fn remove_from_group(slf: &PyCell<Self>, py: Python, group: &str) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        let Some(index) = slf.borrow().groups.iter().position(|name| name == group) else {
+            return Ok(());
+        };
+        slf.borrow_mut().groups.remove(index);
+        if let Some(tree) = &slf.borrow().tree {
+            tree.borrow_mut(py).unregister_group(py, group, slf);
+        }
+        Ok(())
+    }
+    pub fn is_in_group(&self, group: &str) -> PyResult<bool> {
+        self.ensure_valid()?;
+        Ok(self.groups.iter().any(|name| name == group))
+    }
+    pub fn get_groups(&self) -> PyResult<Vec<String>> {
+        self.ensure_valid()?;
+        Ok(self.groups.clone())
+    }
+    #[setter]
+    pub fn set_process_mode(&mut self, py: Python, process_mode: u8) -> PyResult<()> {
+        if process_mode > Node::PROCESS_MODE_DISABLED {
+            return Err(PyValueError::new_err(format!(
+                "{process_mode} is not a valid process mode"
+            )));
+        }
+        self.process_mode = process_mode;
+        self.invalidate_process_order(py);
+        Ok(())
+    }
+    #[setter]
+    pub fn set_process_priority(&mut self, py: Python, priority: i32) {
+        self.process_priority = priority;
+        self.invalidate_process_order(py);
+    }
+    #[setter]
+    pub fn set_physics_priority(&mut self, py: Python, priority: i32) {
+        self.physics_priority = priority;
+        self.invalidate_process_order(py);
+    }
+    // Whether the node's callbacks would currently be called, given the tree's pause state
+    pub fn can_process(slf: &PyCell<Self>, py: Python) -> PyResult<bool> {
+        slf.borrow().ensure_valid()?;
+        let paused = Node::tree_paused(slf, py);
+        let mut mode = slf.borrow().process_mode;
+        let mut parent = slf.borrow().parent.clone();
+        while mode == Node::PROCESS_MODE_INHERIT {
+            let Some(current) = parent else {
+                break;
+            };
+            let node = as_node(py, &current)?.borrow();
+            mode = node.process_mode;
+            parent = node.parent.clone();
+        }
+        Ok(Node::mode_can_process(mode, paused))
+    }
+    #[pyo3(signature = (name, *arg_types))]
+    pub fn add_user_signal(slf: &PyCell<Self>, name: String, arg_types: &PyTuple) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        slf.borrow_mut()
+            .signals
+            .entry(name)
+            .or_insert_with(|| SignalSlot::new(Vec::new()))
+            .arg_types = arg_types.extract()?;
+        Ok(())
+    }
+    pub fn has_signal(slf: &PyCell<Self>, py: Python, name: &str) -> PyResult<bool> {
+        slf.borrow().ensure_valid()?;
+        Node::declare_signal(slf, py, name)
+    }
+    #[pyo3(signature = (signal, callable, flags = 0))]
+    pub fn connect(
+        slf: &PyCell<Self>,
+        py: Python,
+        signal: &str,
+        callable: PyObject,
+        flags: u8,
+    ) -> PyResult<()> {
+        if Node::is_connected(slf, py, signal, callable.clone_ref(py))? {
+            return Err(PyValueError::new_err(format!(
+                "This callable is already connected to the signal '{signal}'"
+            )));
+        }
+        if !callable.as_ref(py).is_callable() {
+            return Err(PyTypeError::new_err(
+                "Only callables can be connected to signals",
+            ));
+        }
+        let target = callable
+            .getattr(py, "__self__")
+            .ok()
+            .filter(|target| target.as_ref(py).is_instance_of::<Node>().unwrap_or(false));
+        slf.borrow_mut()
+            .signals
+            .get_mut(signal)
+            .unwrap()
+            .connections
+            .push(Connection {
+                callable,
+                target,
+                flags,
+            });
+        Ok(())
+    }
+    pub fn disconnect(
+        slf: &PyCell<Self>,
+        py: Python,
+        signal: &str,
+        callable: PyObject,
+    ) -> PyResult<()> {
+        let index = Node::find_connection(slf, py, signal, &callable)?.ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "This callable is not connected to the signal '{signal}'"
+            ))
+        })?;
+        slf.borrow_mut()
+            .signals
+            .get_mut(signal)
+            .unwrap()
+            .connections
+            .remove(index);
+        Ok(())
+    }
+    pub fn is_connected(
+        slf: &PyCell<Self>,
+        py: Python,
+        signal: &str,
+        callable: PyObject,
+    ) -> PyResult<bool> {
+        Ok(Node::find_connection(slf, py, signal, &callable)?.is_some())
+    }
+    #[pyo3(signature = (signal, *args))]
+    pub fn emit(slf: &PyCell<Self>, py: Python, signal: &str, args: &PyTuple) -> PyResult<()> {
+        Node::ensure_signal(slf, py, signal)?;
+        slf.borrow().signals[signal].check_args(py, signal, args)?;
+        // Connections to freed nodes and one-shot connections are dropped before anything is called
+        let connections = {
+            let mut node = slf.borrow_mut();
+            // Nodes can connect to their own signals, and they're already borrowed here
+            let freed = node.freed;
+            let slot = node.signals.get_mut(signal).unwrap();
+            slot.connections.retain(|connection| {
+                !connection.target.as_ref().map_or(false, |target| {
+                    if target.as_ref(py).is(slf) {
+                        return freed;
+                    }
+                    as_node(py, target).map_or(true, |target| target.borrow().freed)
+                })
+            });
+            let connections = slot.connections.clone();
+            slot.connections
+                .retain(|connection| connection.flags & Node::CONNECT_ONE_SHOT == 0);
+            connections
+        };
+        let tree = slf.borrow().tree.clone();
+        for connection in connections {
+            match &tree {
+                Some(tree) if connection.flags & Node::CONNECT_DEFERRED != 0 => {
+                    tree.borrow_mut(py).deferred_calls.push(DeferredCall {
+                        callable: connection.callable,
+                        args: args.into(),
+                        target: connection.target,
+                    })
+                }
+                Some(tree) => {
+                    let result = connection.callable.call1(py, args)?;
+                    NodeTree::start_coroutine(
+                        tree,
+                        py,
+                        result.as_ref(py),
+                        connection.target,
+                        &format!("a '{signal}' callback"),
+                    )?;
+                }
+                None => {
+                    connection.callable.call1(py, args)?;
+                }
+            }
+        }
+        Ok(())
+    }
+    // Calls the method once the current frame's callbacks have run, skipped if the node is freed first
+    #[pyo3(signature = (method, *args))]
+    pub fn call_deferred(
+        slf: &PyCell<Self>,
+        py: Python,
+        method: &str,
+        args: &PyTuple,
+    ) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        let tree = slf.borrow().tree.clone();
+        let Some(tree) = tree else {
+            return Err(PyValueError::new_err(
+                "Only nodes inside a tree can defer calls!",
+            ));
+        };
+        let callable = slf.getattr(method)?.into();
+        tree.borrow_mut(py).deferred_calls.push(DeferredCall {
+            callable,
+            args: args.into(),
+            target: Some(slf.into()),
+        });
+        Ok(())
+    }
+    // Returns an awaitable that completes with the arguments of the signal's next emission
+    pub fn signal(slf: &PyCell<Self>, py: Python, name: &str) -> PyResult<Py<Awaitable>> {
+        Node::ensure_signal(slf, py, name)?;
+        let awaitable = Py::new(py, Awaitable::new(py, 0.0, true))?;
+        Node::connect(
+            slf,
+            py,
+            name,
+            awaitable.clone_ref(py).into_py(py),
+            Node::CONNECT_ONE_SHOT,
+        )?;
+        Ok(awaitable)
+    }
     pub fn _ready_recursive(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
-        for pytype in &slf.borrow().mro {
-            pytype.call_method1(py, "_ready", (slf,))?;
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::_ready_recursive(as_node(py, &child)?, py)?;
+        }
+        if !slf.borrow().is_ready {
+            slf.borrow_mut().is_ready = true;
+            builtin_nodes::internal_ready(slf)?;
+            Node::notify(slf, "_ready", Node::NOTIFICATION_READY)?;
         }
         Ok(())
     }
-    pub fn _process_recursive(slf: &PyCell<Self>, py: Python, delta: f64) -> PyResult<()> {
-        for pytype in &slf.borrow().mro {
-            pytype.call_method1(py, "_process", (slf, delta))?;
+    // Calls `_notification` on the node's classes, then passes it on to its children
+    pub fn propagate_notification(slf: &PyCell<Self>, py: Python, what: i32) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        Node::cache_mro(slf, py)?;
+        Node::call_chain(slf, "_notification", (what,))?;
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::propagate_notification(as_node(py, &child)?, py, what)?;
         }
         Ok(())
     }
+    pub fn notification(slf: &PyCell<Self>, py: Python, what: i32) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        Node::cache_mro(slf, py)?;
+        Node::call_chain(slf, "_notification", (what,))
+    }
     pub fn _on_key_input_recursive(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
-        for pytype in &slf.borrow().mro {
-            pytype.call_method1(py, "_on_key_input", (slf,))?;
+        Node::propagate(slf, py, "_on_key_input", PyTuple::empty(py).into())
+    }
+    pub fn __traverse__(&self, visit: PyVisit) -> Result<(), PyTraverseError> {
+        if let Some(parent) = &self.parent {
+            visit.call(parent)?;
+        }
+        if let Some(tree) = &self.tree {
+            visit.call(tree)?;
+        }
+        for slot in self.signals.values() {
+            slot.traverse(&visit)?;
+        }
+        self.children
+            .iter()
+            .try_for_each(|py_object| -> Result<(), PyTraverseError> { visit.call(py_object) })
+    }
+    pub fn __clear__(&mut self) {
+        self.parent = None;
+        self.children.clear();
+        self.signals.clear();
+        self.tree = None;
+    }
+}
+
+impl Node {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Self::default()
+        }
+    }
+    // Calls `method` on every class in the node's mro that defines it, base classes first
+    pub fn call_chain(
+        slf: &PyCell<Self>,
+        method: &str,
+        args: impl IntoPy<Py<PyTuple>>,
+    ) -> PyResult<()> {
+        let py = slf.py();
+        let args = args.into_py(py);
+        let (mro, override_callbacks) = {
+            let node = slf.borrow();
+            (node.mro.clone(), node.override_callbacks)
+        };
+        if override_callbacks {
+            // Only the most derived definition runs, and it can call `super()` itself
+            let result = slf.getattr(method)?.call1(args.as_ref(py));
+            Node::handle_callback_result(slf, py, method, result)?;
+            return Ok(());
+        }
+        let mut args: Vec<PyObject> = args.as_ref(py).iter().map(Into::into).collect();
+        args.insert(0, slf.into());
+        let args = PyTuple::new(py, args);
+        for pytype in mro {
+            let pytype = pytype.as_ref(py);
+            if pytype.getattr("__dict__")?.contains(method)? {
+                let result = pytype.getattr(method)?.call1(args);
+                if !Node::handle_callback_result(slf, py, method, result)? {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+    // Starts the callback's coroutine if it returned one, or handles its error.
+    // Returns false if an error was handled, which stops the rest of the chain
+    fn handle_callback_result(
+        slf: &PyCell<Self>,
+        py: Python,
+        method: &str,
+        result: PyResult<&PyAny>,
+    ) -> PyResult<bool> {
+        let tree = slf.borrow().tree.clone();
+        match (result, tree) {
+            (Ok(result), Some(tree)) => {
+                NodeTree::start_coroutine(&tree, py, result, Some(slf.into()), method)?;
+                Ok(true)
+            }
+            (Ok(_), None) => Ok(true),
+            (Err(err), Some(tree)) => {
+                NodeTree::handle_error(&tree, py, slf, method, err)?;
+                Ok(false)
+            }
+            (Err(err), None) => Err(err),
+        }
+    }
+    // Emits a signal from the node's own processing, so errors in the connected callbacks
+    // follow the tree's error policy like errors in the node's callbacks do
+    pub fn emit_handled(
+        slf: &PyCell<Self>,
+        py: Python,
+        signal: &str,
+        args: &PyTuple,
+    ) -> PyResult<()> {
+        let result = Node::emit(slf, py, signal, args);
+        let tree = slf.borrow().tree.clone();
+        match (result, tree) {
+            (Err(err), Some(tree)) => NodeTree::handle_error(&tree, py, slf, signal, err),
+            (result, _) => result,
+        }
+    }
+    // Calls `method` on the node and then its children, skipping any node whose
+    // process mode doesn't allow it to run while the tree is (or isn't) paused
+    fn propagate(slf: &PyCell<Self>, py: Python, method: &str, args: Py<PyTuple>) -> PyResult<()> {
+        let paused = Node::tree_paused(slf, py);
+        Node::propagate_with_mode(slf, py, method, &args, paused, Node::PROCESS_MODE_PAUSABLE)
+    }
+    fn propagate_with_mode(
+        slf: &PyCell<Self>,
+        py: Python,
+        method: &str,
+        args: &Py<PyTuple>,
+        paused: bool,
+        inherited_mode: u8,
+    ) -> PyResult<()> {
+        let mode = match slf.borrow().process_mode {
+            Node::PROCESS_MODE_INHERIT => inherited_mode,
+            mode => mode,
+        };
+        if Node::mode_can_process(mode, paused) {
+            Node::call_chain(slf, method, args.as_ref(py))?;
+        }
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::propagate_with_mode(as_node(py, &child)?, py, method, args, paused, mode)?;
+        }
+        Ok(())
+    }
+    // Appends the node and its children to `entries` in tree order, along with
+    // the process mode each of them ends up with after inheriting from its parents
+    pub fn flatten(
+        slf: &PyCell<Self>,
+        py: Python,
+        inherited_mode: u8,
+        entries: &mut Vec<ProcessEntry>,
+    ) -> PyResult<()> {
+        let mode = match slf.borrow().process_mode {
+            Node::PROCESS_MODE_INHERIT => inherited_mode,
+            mode => mode,
+        };
+        entries.push(ProcessEntry {
+            node: slf.into(),
+            mode,
+            process_priority: slf.borrow().process_priority,
+            physics_priority: slf.borrow().physics_priority,
+        });
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::flatten(as_node(py, &child)?, py, mode, entries)?;
+        }
+        Ok(())
+    }
+    // Stops the node from being processed, along with the children that inherit its mode
+    pub fn disable(&mut self, py: Python) {
+        self.process_mode = Node::PROCESS_MODE_DISABLED;
+        self.invalidate_process_order(py);
+    }
+    fn invalidate_process_order(&self, py: Python) {
+        if let Some(tree) = &self.tree {
+            tree.borrow_mut(py).order_dirty = true;
+        }
+    }
+    pub const fn mode_can_process(mode: u8, paused: bool) -> bool {
+        match mode {
+            Node::PROCESS_MODE_WHEN_PAUSED => paused,
+            Node::PROCESS_MODE_ALWAYS => true,
+            Node::PROCESS_MODE_DISABLED => false,
+            _ => !paused,
+        }
+    }
+    fn tree_paused(slf: &PyCell<Self>, py: Python) -> bool {
+        slf.borrow()
+            .tree
+            .as_ref()
+            .map_or(false, |tree| tree.borrow(py).paused)
+    }
+    pub fn is_ancestor_of(slf: &PyCell<Self>, py: Python, node: &PyCell<Node>) -> PyResult<bool> {
+        let mut parent = node.borrow().parent.clone();
+        while let Some(current) = parent {
+            if current.is(slf) {
+                return Ok(true);
+            }
+            parent = as_node(py, &current)?.borrow().parent.clone();
+        }
+        Ok(false)
+    }
+    pub fn get_child_by_name(
+        slf: &PyCell<Self>,
+        py: Python,
+        name: &str,
+    ) -> PyResult<Option<PyObject>> {
+        for child in &slf.borrow().children {
+            if as_node(py, child)?.borrow().name == name {
+                return Ok(Some(child.clone_ref(py)));
+            }
+        }
+        Ok(None)
+    }
+    // Renames `node` so that none of its siblings share its name, e.g. "Enemy" -> "Enemy2"
+    fn make_name_unique(slf: &PyCell<Self>, py: Python, node: &PyCell<Node>) -> PyResult<()> {
+        let taken = |name: &str| -> PyResult<bool> {
+            for child in &slf.borrow().children {
+                let child = as_node(py, child)?;
+                if !child.is(node) && child.borrow().name == name {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        };
+        let name = node.borrow().name.clone();
+        if !taken(&name)? {
+            return Ok(());
+        }
+        let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+        let mut index: usize = name[base.len()..].parse().unwrap_or(1);
+        loop {
+            index += 1;
+            let candidate = format!("{base}{index}");
+            if !taken(&candidate)? {
+                node.borrow_mut().name = candidate;
+                return Ok(());
+            }
+        }
+    }
+    // Caches the classes `call_chain` walks through, leaving out `Node` and `object`
+    fn cache_mro(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        if slf.borrow().mro.is_empty() {
+            let pytype = slf.get_pytype(py);
+            let mut bases = pytype.getattr("__mro__")?.extract::<Vec<Py<PyType>>>()?;
+            bases.remove(bases.len() - 1);
+            bases.remove(bases.len() - 1);
+            bases.reverse();
+            let chain = pytype.getattr("__ferrokrait_chain__")?.is_true()?;
+            let mut node = slf.borrow_mut();
+            node.mro = bases;
+            node.override_callbacks = !chain;
+        }
+        Ok(())
+    }
+    // Calls the callback and then `_notification` with the matching constant
+    fn notify(slf: &PyCell<Self>, method: &str, what: i32) -> PyResult<()> {
+        Node::call_chain(slf, method, ())?;
+        Node::call_chain(slf, "_notification", (what,))
+    }
+    // Gives the node and its children a reference to the tree, calling `_enter_tree` on
+    // the node before its children
+    fn enter_tree(slf: &PyCell<Self>, py: Python, tree: &Py<NodeTree>) -> PyResult<()> {
+        // A child added by the parent's `_enter_tree` has already entered
+        if slf.borrow().tree.is_some() {
+            return Ok(());
+        }
+        Node::cache_mro(slf, py)?;
+        slf.borrow_mut().tree = Some(tree.clone_ref(py));
+        tree.borrow_mut(py).order_dirty = true;
+        for group in slf.borrow().groups.iter() {
+            tree.borrow_mut(py).register_group(group, slf.into());
+        }
+        Node::notify(slf, "_enter_tree", Node::NOTIFICATION_ENTER_TREE)?;
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::enter_tree(as_node(py, &child)?, py, tree)?;
+        }
+        Ok(())
+    }
+    // Registers a signal declared on the node's class the first time it is used
+    fn declare_signal(slf: &PyCell<Self>, py: Python, name: &str) -> PyResult<bool> {
+        if slf.borrow().signals.contains_key(name) {
+            return Ok(true);
+        }
+        let Ok(attribute) = slf.get_pytype(py).getattr(name) else {
+            return Ok(false);
+        };
+        let Ok(signal) = attribute.extract::<Signal>() else {
+            return Ok(false);
+        };
+        slf.borrow_mut()
+            .signals
+            .insert(name.to_owned(), SignalSlot::new(signal.arg_types));
+        Ok(true)
+    }
+    fn ensure_signal(slf: &PyCell<Self>, py: Python, signal: &str) -> PyResult<()> {
+        slf.borrow().ensure_valid()?;
+        if !Node::declare_signal(slf, py, signal)? {
+            return Err(PyLookupError::new_err(format!(
+                "The node '{0}' has no signal named '{signal}'",
+                slf.borrow().name
+            )));
+        }
+        Ok(())
+    }
+    fn find_connection(
+        slf: &PyCell<Self>,
+        py: Python,
+        signal: &str,
+        callable: &PyObject,
+    ) -> PyResult<Option<usize>> {
+        Node::ensure_signal(slf, py, signal)?;
+        for (index, connection) in slf.borrow().signals[signal].connections.iter().enumerate() {
+            if connection.callable.as_ref(py).eq(callable)? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+    // Calls `_exit_tree` on the node's children, then on the node itself, before dropping the tree
+    fn exit_tree(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        if slf.borrow().tree.is_none() {
+            return Ok(());
+        }
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::exit_tree(as_node(py, &child)?, py)?;
+        }
+        Node::notify(slf, "_exit_tree", Node::NOTIFICATION_EXIT_TREE)?;
+        let tree = slf.borrow_mut().tree.take();
+        if let Some(tree) = tree {
+            tree.borrow_mut(py).order_dirty = true;
+            for group in slf.borrow().groups.iter() {
+                tree.borrow_mut(py).unregister_group(py, group, slf);
+            }
+        }
+        Ok(())
+    }
+    fn mark_queued(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        slf.borrow_mut().queued_for_deletion = true;
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::mark_queued(as_node(py, &child)?, py)?;
         }
         Ok(())
     }
+    // Detaches the node from its parent, then invalidates it along with all of its children
+    pub fn free(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        Node::predelete(slf, py)?;
+        let parent = slf.borrow().parent.clone();
+        if let Some(parent) = parent {
+            Node::remove_child(as_node(py, &parent)?, py, slf)?;
+        }
+        Node::invalidate(slf, py)
+    }
+    fn predelete(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        Node::cache_mro(slf, py)?;
+        Node::call_chain(slf, "_notification", (Node::NOTIFICATION_PREDELETE,))?;
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::predelete(as_node(py, &child)?, py)?;
+        }
+        Ok(())
+    }
+    fn invalidate(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        let children = slf.borrow().children.clone();
+        for child in children {
+            Node::invalidate(as_node(py, &child)?, py)?;
+        }
+        let mut node = slf.borrow_mut();
+        node.freed = true;
+        node.queued_for_deletion = true;
+        node.parent = None;
+        node.children.clear();
+        node.signals.clear();
+        Ok(())
+    }
+    pub const fn is_freed(&self) -> bool {
+        self.freed
+    }
+    pub fn ensure_valid(&self) -> PyResult<()> {
+        if self.freed {
+            return Err(PyReferenceError::new_err(format!(
+                "The node '{0}' has been freed and can no longer be used",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+// Shorthand for downcasting a python object that is known to be a node
+pub fn as_node<'a>(py: Python<'a>, object: &'a PyObject) -> PyResult<&'a PyCell<Node>> {
+    Ok(object.as_ref(py).downcast()?)
+}
+
+// Creates an instance of a python subclass through the nearest ferrokrait class, whose
+// constructor takes no arguments. The arguments are left to the subclass's `__init__`, and
+// `object.__init__` doesn't reject them once `__new__` is overridden, so that's done here
+#[pyfunction]
+#[pyo3(signature = (cls, *args, **kwargs))]
+fn new_subclass<'py>(
+    py: Python<'py>,
+    cls: &'py PyType,
+    args: &PyTuple,
+    kwargs: Option<&PyDict>,
+) -> PyResult<&'py PyAny> {
+    let has_arguments = !args.is_empty() || kwargs.map_or(false, |kwargs| !kwargs.is_empty());
+    let object_init = py.get_type::<PyAny>().getattr("__init__")?;
+    if has_arguments && cls.getattr("__init__")?.is(object_init) {
+        return Err(PyTypeError::new_err(format!(
+            "{}() takes no arguments",
+            cls.name()?
+        )));
+    }
+    for class in cls.getattr("__mro__")?.iter()? {
+        let class = class?;
+        if class.getattr("__module__")?.extract::<&str>()? == "builtins" {
+            return class.getattr("__new__")?.call1((cls,));
+        }
+    }
+    Err(PyTypeError::new_err(format!(
+        "'{}' isn't a subclass of a ferrokrait class",
+        cls.name()?
+    )))
+}
+
+#[pyfunction]
+pub fn is_instance_valid(py: Python, object: Option<PyObject>) -> bool {
+    match object {
+        Some(object) => match object.as_ref(py).downcast::<PyCell<Node>>() {
+            Ok(node) => !node.borrow().freed,
+            Err(_) => true,
+        },
+        None => false,
+    }
 }
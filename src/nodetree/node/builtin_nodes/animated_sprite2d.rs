@@ -0,0 +1,320 @@
+use pyo3::prelude::*;
+use pyo3::{
+    exceptions::{PyIndexError, PyValueError},
+    types::PyTuple,
+};
+use std::collections::HashMap;
+
+use super::all::*;
+use crate::builtin_types::all::*;
+use crate::nodetree::node::signal::Signal;
+
+#[derive(Debug, Clone)]
+struct Frame {
+    texture: Py<Image>,
+    region: Option<(Vec2, Vec2)>,
+}
+
+#[derive(Debug, Clone)]
+struct Animation {
+    frames: Vec<Frame>,
+    fps: f64,
+    looping: bool,
+}
+
+// Plays named animations by switching the texture and region of the sprite every frame
+#[pyclass(subclass, extends = Sprite2D)]
+#[derive(Default, Debug, Clone)]
+pub struct AnimatedSprite2D {
+    animations: HashMap<String, Animation>,
+    #[pyo3(get)]
+    animation: String,
+    #[pyo3(get)]
+    frame: usize,
+    #[pyo3(get)]
+    speed_scale: f64,
+    playing: bool,
+    // How far the current frame is towards the next one, from 0 to 1
+    frame_progress: f64,
+}
+
+#[pymethods]
+impl AnimatedSprite2D {
+    // Emitted when an animation that doesn't loop reaches its last frame
+    #[classattr]
+    pub fn animation_finished() -> Signal {
+        Signal::default()
+    }
+    #[classattr]
+    #[pyo3(name = "__export__")]
+    pub fn export() -> Vec<&'static str> {
+        vec!["speed_scale"]
+    }
+    #[new]
+    pub fn new() -> PyClassInitializer<Self> {
+        Sprite2D::new().add_subclass(Self {
+            speed_scale: 1.0,
+            ..Self::default()
+        })
+    }
+    // An animation that shows each image in turn
+    #[pyo3(signature = (name, frames, fps = 5.0, looping = true))]
+    pub fn add_animation(
+        mut slf: PyRefMut<Self>,
+        name: String,
+        frames: Vec<Py<Image>>,
+        fps: f64,
+        looping: bool,
+    ) -> PyResult<()> {
+        let frames = frames
+            .into_iter()
+            .map(|texture| Frame {
+                texture,
+                region: None,
+            })
+            .collect();
+        slf.insert_animation(name, frames, fps, looping)?;
+        AnimatedSprite2D::show_frame(&mut slf);
+        Ok(())
+    }
+    // An animation from a sprite sheet split into a grid of `hframes` by `vframes` cells.
+    // `frames` are the cells to show, counting left to right and then top to bottom, or all of them
+    #[pyo3(signature = (name, texture, hframes, vframes, frames = None, fps = 5.0, looping = true))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_animation_from_grid(
+        mut slf: PyRefMut<Self>,
+        py: Python,
+        name: String,
+        texture: Py<Image>,
+        hframes: usize,
+        vframes: usize,
+        frames: Option<Vec<usize>>,
+        fps: f64,
+        looping: bool,
+    ) -> PyResult<()> {
+        if hframes == 0 || vframes == 0 {
+            return Err(PyValueError::new_err(
+                "A sprite sheet needs at least one row and column",
+            ));
+        }
+        let (width, height) = {
+            let texture = texture.try_borrow(py)?;
+            (texture.width / hframes, texture.height / vframes)
+        };
+        if width == 0 || height == 0 {
+            return Err(PyValueError::new_err(format!(
+                "The texture is too small to split into a {hframes}x{vframes} grid"
+            )));
+        }
+        let cells = frames.unwrap_or_else(|| (0..hframes * vframes).collect());
+        let frames = cells
+            .into_iter()
+            .map(|cell| {
+                if cell >= hframes * vframes {
+                    return Err(PyIndexError::new_err(format!(
+                        "Cell {cell} is outside of the {hframes}x{vframes} grid"
+                    )));
+                }
+                let position = Vec2::new(
+                    (cell % hframes * width) as f64,
+                    (cell / hframes * height) as f64,
+                );
+                Ok(Frame {
+                    texture: texture.clone_ref(py),
+                    region: Some((position, Vec2::new(width as f64, height as f64))),
+                })
+            })
+            .collect::<PyResult<_>>()?;
+        slf.insert_animation(name, frames, fps, looping)?;
+        AnimatedSprite2D::show_frame(&mut slf);
+        Ok(())
+    }
+    // Removing the current animation stops playback and leaves the sprite without one
+    pub fn remove_animation(mut slf: PyRefMut<Self>, name: &str) {
+        slf.animations.remove(name);
+        if slf.animation == name {
+            slf.animation.clear();
+            slf.playing = false;
+            slf.frame = 0;
+            slf.frame_progress = 0.0;
+        }
+        AnimatedSprite2D::show_frame(&mut slf);
+    }
+    pub fn has_animation(&self, name: &str) -> bool {
+        self.animations.contains_key(name)
+    }
+    pub fn get_animation_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.animations.keys().cloned().collect();
+        names.sort();
+        names
+    }
+    // Switches to the animation from its first frame, without playing it
+    #[setter]
+    pub fn set_animation(mut slf: PyRefMut<Self>, animation: String) -> PyResult<()> {
+        slf.switch_animation(animation)?;
+        AnimatedSprite2D::show_frame(&mut slf);
+        Ok(())
+    }
+    #[setter]
+    pub fn set_speed_scale(&mut self, speed_scale: f64) -> PyResult<()> {
+        if !speed_scale.is_finite() || speed_scale < 0.0 {
+            return Err(PyValueError::new_err(
+                "speed_scale must be a finite number of at least 0",
+            ));
+        }
+        self.speed_scale = speed_scale;
+        Ok(())
+    }
+    #[setter]
+    pub fn set_frame(mut slf: PyRefMut<Self>, frame: usize) -> PyResult<()> {
+        let count = slf.ensure_animation(&slf.animation)?.frames.len();
+        if frame >= count {
+            return Err(PyIndexError::new_err(format!(
+                "Frame {frame} is outside of the {count} frames of '{0}'",
+                slf.animation
+            )));
+        }
+        slf.frame = frame;
+        slf.frame_progress = 0.0;
+        AnimatedSprite2D::show_frame(&mut slf);
+        Ok(())
+    }
+    // Plays the animation, or resumes the current one. Animations that finished start over
+    #[pyo3(signature = (name = None))]
+    pub fn play(mut slf: PyRefMut<Self>, name: Option<String>) -> PyResult<()> {
+        match name {
+            Some(name) if name != slf.animation => slf.switch_animation(name)?,
+            _ => {
+                let animation = slf.ensure_animation(&slf.animation)?;
+                let finished =
+                    !slf.playing && !animation.looping && slf.frame + 1 == animation.frames.len();
+                if finished {
+                    slf.frame = 0;
+                    slf.frame_progress = 0.0;
+                }
+            }
+        }
+        slf.playing = true;
+        AnimatedSprite2D::show_frame(&mut slf);
+        Ok(())
+    }
+    // Stops playing and goes back to the first frame
+    pub fn stop(mut slf: PyRefMut<Self>) {
+        slf.playing = false;
+        slf.frame = 0;
+        slf.frame_progress = 0.0;
+        AnimatedSprite2D::show_frame(&mut slf);
+    }
+    // Stops playing and stays on the current frame
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+    pub const fn is_playing(&self) -> bool {
+        self.playing
+    }
+}
+
+impl AnimatedSprite2D {
+    fn ensure_animation(&self, name: &str) -> PyResult<&Animation> {
+        self.animations
+            .get(name)
+            .ok_or_else(|| PyValueError::new_err(format!("There's no animation called '{name}'")))
+    }
+    fn switch_animation(&mut self, name: String) -> PyResult<()> {
+        self.ensure_animation(&name)?;
+        self.animation = name;
+        self.frame = 0;
+        self.frame_progress = 0.0;
+        Ok(())
+    }
+    fn insert_animation(
+        &mut self,
+        name: String,
+        frames: Vec<Frame>,
+        fps: f64,
+        looping: bool,
+    ) -> PyResult<()> {
+        if frames.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "The animation '{name}' has no frames"
+            )));
+        }
+        if !fps.is_finite() || fps < 0.0 {
+            return Err(PyValueError::new_err(
+                "fps must be a finite number of at least 0",
+            ));
+        }
+        // Replacing the current animation starts it over, as its frames may have changed
+        if self.animation.is_empty() || self.animation == name {
+            self.animation = name.clone();
+            self.frame = 0;
+            self.frame_progress = 0.0;
+        }
+        self.animations.insert(
+            name,
+            Animation {
+                frames,
+                fps,
+                looping,
+            },
+        );
+        Ok(())
+    }
+    // Points the sprite at the current frame, or at nothing if there isn't one
+    fn show_frame(slf: &mut PyRefMut<Self>) {
+        let frame = slf
+            .animations
+            .get(&slf.animation)
+            .and_then(|animation| animation.frames.get(slf.frame))
+            .cloned();
+        let sprite: &mut Sprite2D = slf.as_mut();
+        sprite.texture = frame.as_ref().map(|frame| frame.texture.clone());
+        sprite.region_rect = frame.and_then(|frame| frame.region);
+    }
+    pub fn internal_process(
+        slf: &PyCell<Self>,
+        py: Python,
+        delta: f64,
+        physics: bool,
+    ) -> PyResult<()> {
+        if physics {
+            return Ok(());
+        }
+        {
+            let mut sprite = slf.borrow_mut();
+            if !sprite.playing {
+                return Ok(());
+            }
+            let Some(animation) = sprite.animations.get(&sprite.animation) else {
+                return Ok(());
+            };
+            let (count, fps, looping) = (animation.frames.len(), animation.fps, animation.looping);
+            // Capped so that huge steps still leave a number of frames to advance by
+            let progress = (sprite.frame_progress + delta * fps * sprite.speed_scale).min(f64::MAX);
+            let steps = progress.floor();
+            sprite.frame_progress = progress - steps;
+            let remaining = (count - 1 - sprite.frame) as f64;
+            // The last frame is shown for a whole step before the animation finishes
+            let finished = !looping && steps > remaining;
+            if finished {
+                sprite.playing = false;
+                sprite.frame = count - 1;
+                sprite.frame_progress = 0.0;
+            } else if looping {
+                sprite.frame = (sprite.frame + (steps % count as f64) as usize) % count;
+            } else {
+                sprite.frame += steps as usize;
+            }
+            AnimatedSprite2D::show_frame(&mut sprite);
+            if !finished {
+                return Ok(());
+            }
+        }
+        Node::emit_handled(
+            slf.downcast()?,
+            py,
+            "animation_finished",
+            PyTuple::empty(py),
+        )
+    }
+}
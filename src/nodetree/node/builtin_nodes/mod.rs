@@ -1,6 +1,39 @@
+pub mod animated_sprite2d;
 pub mod node2d;
+pub mod sprite2d;
+pub mod timer;
 
 pub mod all {
-    pub use super::node2d::*;
     pub use super::super::Node;
+    pub use super::animated_sprite2d::*;
+    pub use super::node2d::*;
+    pub use super::sprite2d::*;
+    pub use super::timer::*;
+}
+
+use pyo3::prelude::*;
+
+use all::*;
+
+// Lets builtin nodes update themselves, independently of any callbacks overridden in python
+pub fn internal_ready(node: &PyCell<Node>) -> PyResult<()> {
+    if let Ok(timer) = node.downcast::<PyCell<Timer>>() {
+        timer.borrow_mut().internal_ready()?;
+    }
+    Ok(())
+}
+
+pub fn internal_process(
+    node: &PyCell<Node>,
+    py: Python,
+    delta: f64,
+    physics: bool,
+) -> PyResult<()> {
+    if let Ok(timer) = node.downcast::<PyCell<Timer>>() {
+        Timer::internal_process(timer, py, delta, physics)?;
+    }
+    if let Ok(sprite) = node.downcast::<PyCell<AnimatedSprite2D>>() {
+        AnimatedSprite2D::internal_process(sprite, py, delta, physics)?;
+    }
+    Ok(())
 }
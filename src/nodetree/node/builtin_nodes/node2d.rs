@@ -1,25 +1,240 @@
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 use super::all::*;
 use crate::builtin_types::all::*;
+use crate::render::canvas::DrawCommand;
 
 #[pyclass(subclass, extends = Node)]
 #[derive(Default, Debug, Clone)]
 pub struct Node2D {
+    #[pyo3(get, set)]
     position: Vec2,
+    #[pyo3(get, set)]
     rotation_rad: f64,
+    #[pyo3(get, set)]
+    scale: Vec2,
+    #[pyo3(get, set)]
+    skew: f64,
+    // Hides the node and its children from the renderer
+    #[pyo3(get, set)]
+    pub(crate) visible: bool,
+    pub(crate) draw_commands: Vec<DrawCommand>,
+    // `_draw` runs again before the next render when set
+    redraw_queued: bool,
+    // The draw methods only work while `_draw` is running
+    drawing: bool,
 }
 
 #[pymethods]
 impl Node2D {
+    #[classattr]
+    #[pyo3(name = "__export__")]
+    pub fn export() -> Vec<&'static str> {
+        vec!["position", "rotation_rad", "scale", "skew", "visible"]
+    }
     #[new]
     pub fn new() -> PyClassInitializer<Self> {
         PyClassInitializer::from(Node::new()).add_subclass(Self {
             position: Vec2::ZERO,
             rotation_rad: 0.0,
+            scale: Vec2::ONE,
+            skew: 0.0,
+            visible: true,
+            draw_commands: Vec::new(),
+            redraw_queued: true,
+            drawing: false,
         })
     }
     pub fn test(slf: PyRef<Self>) {
         let _t = slf.as_ref();
     }
+    pub const fn _draw(&self) {}
+    // Records the node's drawing again with `_draw` before the next render
+    pub fn queue_redraw(&mut self) {
+        self.redraw_queued = true;
+    }
+    #[pyo3(signature = (start, end, color, width = 1.0))]
+    pub fn draw_line(&mut self, start: Vec2, end: Vec2, color: Color, width: f64) -> PyResult<()> {
+        self.record(DrawCommand::Line {
+            start,
+            end,
+            color,
+            width,
+        })
+    }
+    #[pyo3(signature = (position, size, color, filled = true, width = 1.0))]
+    pub fn draw_rect(
+        &mut self,
+        position: Vec2,
+        size: Vec2,
+        color: Color,
+        filled: bool,
+        width: f64,
+    ) -> PyResult<()> {
+        self.record(DrawCommand::Rect {
+            position,
+            size,
+            color,
+            filled,
+            width,
+        })
+    }
+    #[pyo3(signature = (center, radius, color, filled = true, width = 1.0))]
+    pub fn draw_circle(
+        &mut self,
+        center: Vec2,
+        radius: f64,
+        color: Color,
+        filled: bool,
+        width: f64,
+    ) -> PyResult<()> {
+        self.record(DrawCommand::Circle {
+            center,
+            radius,
+            color,
+            filled,
+            width,
+        })
+    }
+    #[pyo3(signature = (points, color, filled = true, width = 1.0))]
+    pub fn draw_polygon(
+        &mut self,
+        points: Vec<Vec2>,
+        color: Color,
+        filled: bool,
+        width: f64,
+    ) -> PyResult<()> {
+        self.record(DrawCommand::Polygon {
+            points,
+            color,
+            filled,
+            width,
+        })
+    }
+    #[pyo3(signature = (texture, position, modulate = Color::WHITE))]
+    pub fn draw_texture(
+        &mut self,
+        texture: Py<Image>,
+        position: Vec2,
+        modulate: Color,
+    ) -> PyResult<()> {
+        self.record(DrawCommand::Texture {
+            texture,
+            position,
+            modulate,
+        })
+    }
+    // `position` is the top left corner of the text, and `size` scales the 5x7 pixel font
+    #[pyo3(signature = (position, text, color = Color::WHITE, size = 1.0))]
+    pub fn draw_string(
+        &mut self,
+        position: Vec2,
+        text: String,
+        color: Color,
+        size: f64,
+    ) -> PyResult<()> {
+        self.record(DrawCommand::String {
+            text,
+            position,
+            color,
+            size,
+        })
+    }
+    #[getter]
+    pub fn get_transform(&self) -> Transform2D {
+        Transform2D::from_components(self.rotation_rad, self.position, self.scale, self.skew)
+    }
+    #[setter]
+    pub fn set_transform(&mut self, transform: Transform2D) {
+        self.position = transform.origin;
+        self.rotation_rad = transform.get_rotation();
+        self.scale = transform.get_scale();
+        self.skew = transform.get_skew();
+    }
+    // Combines the transforms of the node and its parents, up to the first parent that isn't a Node2D
+    #[getter]
+    pub fn get_global_transform(slf: &PyCell<Self>, py: Python) -> PyResult<Transform2D> {
+        Ok(Node2D::parent_transform(slf, py)? * slf.borrow().get_transform())
+    }
+    #[setter]
+    pub fn set_global_transform(
+        slf: &PyCell<Self>,
+        py: Python,
+        transform: Transform2D,
+    ) -> PyResult<()> {
+        let parent = Node2D::parent_transform(slf, py)?.affine_inverse()?;
+        slf.borrow_mut().set_transform(parent * transform);
+        Ok(())
+    }
+    #[getter]
+    pub fn get_global_position(slf: &PyCell<Self>, py: Python) -> PyResult<Vec2> {
+        Ok(Node2D::get_global_transform(slf, py)?.origin)
+    }
+    #[setter]
+    pub fn set_global_position(slf: &PyCell<Self>, py: Python, position: Vec2) -> PyResult<()> {
+        let position = Node2D::parent_transform(slf, py)?.xform_inv(position)?;
+        slf.borrow_mut().position = position;
+        Ok(())
+    }
+    // In radians, like `rotation_rad`
+    #[getter]
+    pub fn get_global_rotation(slf: &PyCell<Self>, py: Python) -> PyResult<f64> {
+        Ok(Node2D::get_global_transform(slf, py)?.get_rotation())
+    }
+    #[setter]
+    pub fn set_global_rotation(slf: &PyCell<Self>, py: Python, rotation: f64) -> PyResult<()> {
+        let parent_rotation = Node2D::parent_transform(slf, py)?.get_rotation();
+        slf.borrow_mut().rotation_rad = rotation - parent_rotation;
+        Ok(())
+    }
+    // Rotates the node so that its x axis points towards a point in global space
+    pub fn look_at(slf: &PyCell<Self>, py: Python, point: Vec2) -> PyResult<()> {
+        let offset = point - Node2D::get_global_position(slf, py)?;
+        Node2D::set_global_rotation(slf, py, offset.y.atan2(offset.x))
+    }
+    pub fn to_local(slf: &PyCell<Self>, py: Python, point: Vec2) -> PyResult<Vec2> {
+        Node2D::get_global_transform(slf, py)?.xform_inv(point)
+    }
+    pub fn to_global(slf: &PyCell<Self>, py: Python, point: Vec2) -> PyResult<Vec2> {
+        Ok(Node2D::get_global_transform(slf, py)?.xform(point))
+    }
+}
+
+impl Node2D {
+    fn record(&mut self, command: DrawCommand) -> PyResult<()> {
+        if !self.drawing {
+            return Err(PyValueError::new_err(
+                "Drawing is only allowed inside _draw, use queue_redraw to draw again!",
+            ));
+        }
+        self.draw_commands.push(command);
+        Ok(())
+    }
+    // Runs `_draw` if a redraw was queued, replacing the recorded commands
+    pub(crate) fn update_draw_commands(slf: &PyCell<Self>) -> PyResult<()> {
+        {
+            let mut node = slf.borrow_mut();
+            if !node.redraw_queued {
+                return Ok(());
+            }
+            node.redraw_queued = false;
+            node.draw_commands.clear();
+            node.drawing = true;
+        }
+        let result = Node::call_chain(slf.downcast()?, "_draw", ());
+        slf.borrow_mut().drawing = false;
+        result
+    }
+    // The global transform of the parent, or the identity if it isn't a Node2D
+    fn parent_transform(slf: &PyCell<Self>, py: Python) -> PyResult<Transform2D> {
+        let parent = slf.borrow().as_ref().parent.clone();
+        let Some(parent) = parent else {
+            return Ok(Transform2D::IDENTITY);
+        };
+        match parent.as_ref(py).downcast::<PyCell<Node2D>>() {
+            Ok(parent) => Node2D::get_global_transform(parent, py),
+            Err(_) => Ok(Transform2D::IDENTITY),
+        }
+    }
 }
@@ -0,0 +1,89 @@
+use pyo3::prelude::*;
+
+use super::all::*;
+use crate::builtin_types::all::*;
+use crate::render::all::Framebuffer;
+
+#[pyclass(subclass, extends = Node2D)]
+#[derive(Default, Debug, Clone)]
+pub struct Sprite2D {
+    #[pyo3(get, set)]
+    pub(crate) texture: Option<Py<Image>>,
+    // The part of the texture to show as (position, size), or all of it when None
+    #[pyo3(get, set)]
+    pub(crate) region_rect: Option<(Vec2, Vec2)>,
+    // Centers the texture on the node's position instead of putting its top left corner there
+    #[pyo3(get, set)]
+    centered: bool,
+    #[pyo3(get, set)]
+    offset: Vec2,
+    #[pyo3(get, set)]
+    flip_h: bool,
+    #[pyo3(get, set)]
+    flip_v: bool,
+    #[pyo3(get, set)]
+    modulate: Color,
+}
+
+#[pymethods]
+impl Sprite2D {
+    #[classattr]
+    #[pyo3(name = "__export__")]
+    pub fn export() -> Vec<&'static str> {
+        vec!["centered", "offset", "flip_h", "flip_v", "modulate"]
+    }
+    #[new]
+    pub fn new() -> PyClassInitializer<Self> {
+        Node2D::new().add_subclass(Self {
+            centered: true,
+            modulate: Color::WHITE,
+            ..Self::default()
+        })
+    }
+    // The area the sprite covers as (position, size) in local space, or None without a texture
+    pub fn get_rect(&self, py: Python) -> PyResult<Option<(Vec2, Vec2)>> {
+        let Some(texture) = &self.texture else {
+            return Ok(None);
+        };
+        let (_, size) = self.region(&*texture.try_borrow(py)?);
+        Ok(Some((self.top_left(size), size)))
+    }
+}
+
+impl Sprite2D {
+    fn region(&self, texture: &Image) -> (Vec2, Vec2) {
+        self.region_rect.unwrap_or((
+            Vec2::ZERO,
+            Vec2::new(texture.width as f64, texture.height as f64),
+        ))
+    }
+    fn top_left(&self, size: Vec2) -> Vec2 {
+        match self.centered {
+            true => self.offset - size * 0.5,
+            false => self.offset,
+        }
+    }
+    pub(crate) fn draw(
+        &self,
+        py: Python,
+        target: &mut Framebuffer,
+        transform: &Transform2D,
+    ) -> PyResult<()> {
+        let Some(texture) = &self.texture else {
+            return Ok(());
+        };
+        let texture = texture.try_borrow(py)?;
+        let (position, size) = self.region(&texture);
+        let top_left = self.top_left(size);
+        // Flipping mirrors the region in place, so the sprite still covers the same area
+        let local = Transform2D::new(
+            Vec2::new(if self.flip_h { -1.0 } else { 1.0 }, 0.0),
+            Vec2::new(0.0, if self.flip_v { -1.0 } else { 1.0 }),
+            Vec2::new(
+                top_left.x + if self.flip_h { size.x } else { 0.0 },
+                top_left.y + if self.flip_v { size.y } else { 0.0 },
+            ),
+        );
+        target.draw_texture_region(&texture, position, size, *transform * local, self.modulate)
+    }
+}
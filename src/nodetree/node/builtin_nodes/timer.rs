@@ -0,0 +1,121 @@
+use pyo3::prelude::*;
+use pyo3::{exceptions::PyValueError, types::PyTuple};
+
+use super::all::*;
+use crate::nodetree::node::signal::Signal;
+
+#[pyclass(subclass, extends = Node)]
+#[derive(Default, Debug, Clone)]
+pub struct Timer {
+    #[pyo3(get)]
+    wait_time: f64,
+    #[pyo3(get, set)]
+    one_shot: bool,
+    #[pyo3(get, set)]
+    autostart: bool,
+    #[pyo3(get, set)]
+    paused: bool,
+    #[pyo3(get)]
+    process_callback: u8,
+    #[pyo3(get)]
+    time_left: f64,
+    stopped: bool,
+}
+
+#[pymethods]
+impl Timer {
+    #[classattr]
+    pub const TIMER_PROCESS_PHYSICS: u8 = 0;
+    #[classattr]
+    pub const TIMER_PROCESS_IDLE: u8 = 1;
+    #[classattr]
+    pub fn timeout() -> Signal {
+        Signal::default()
+    }
+    #[classattr]
+    #[pyo3(name = "__export__")]
+    pub fn export() -> Vec<&'static str> {
+        vec!["wait_time", "one_shot", "autostart", "process_callback"]
+    }
+    #[new]
+    pub fn new() -> PyClassInitializer<Self> {
+        PyClassInitializer::from(Node::new()).add_subclass(Self {
+            wait_time: 1.0,
+            process_callback: Timer::TIMER_PROCESS_IDLE,
+            stopped: true,
+            ..Self::default()
+        })
+    }
+    #[setter]
+    pub fn set_wait_time(&mut self, wait_time: f64) -> PyResult<()> {
+        if wait_time <= 0.0 {
+            return Err(PyValueError::new_err("wait_time must be greater than 0"));
+        }
+        self.wait_time = wait_time;
+        Ok(())
+    }
+    #[setter]
+    pub fn set_process_callback(&mut self, process_callback: u8) -> PyResult<()> {
+        if process_callback > Timer::TIMER_PROCESS_IDLE {
+            return Err(PyValueError::new_err(format!(
+                "{process_callback} is not a valid process callback"
+            )));
+        }
+        self.process_callback = process_callback;
+        Ok(())
+    }
+    // Starts the timer from the beginning, optionally with a new wait time
+    #[pyo3(signature = (time_sec = None))]
+    pub fn start(&mut self, time_sec: Option<f64>) -> PyResult<()> {
+        if let Some(time_sec) = time_sec {
+            self.set_wait_time(time_sec)?;
+        }
+        self.time_left = self.wait_time;
+        self.stopped = false;
+        Ok(())
+    }
+    pub fn stop(&mut self) {
+        self.time_left = 0.0;
+        self.stopped = true;
+    }
+    pub const fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+impl Timer {
+    pub fn internal_ready(&mut self) -> PyResult<()> {
+        if self.autostart && self.stopped {
+            self.start(None)?;
+        }
+        Ok(())
+    }
+    pub fn internal_process(
+        slf: &PyCell<Self>,
+        py: Python,
+        delta: f64,
+        physics: bool,
+    ) -> PyResult<()> {
+        {
+            let mut timer = slf.borrow_mut();
+            let physics_timer = timer.process_callback == Timer::TIMER_PROCESS_PHYSICS;
+            if timer.stopped || timer.paused || physics_timer != physics {
+                return Ok(());
+            }
+            timer.time_left -= delta;
+            if timer.time_left > 0.0 {
+                return Ok(());
+            }
+            if timer.one_shot {
+                timer.stop();
+            } else {
+                // Carrying the overshoot into the next cycle keeps repeating timers from drifting
+                timer.time_left += timer.wait_time;
+                if timer.time_left <= 0.0 {
+                    timer.time_left = timer.wait_time;
+                }
+            }
+        }
+        Node::emit_handled(slf.downcast()?, py, "timeout", PyTuple::empty(py))
+    }
+}
@@ -0,0 +1,81 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyTuple, PyType};
+use pyo3::{exceptions::PyTypeError, PyTraverseError, PyVisit};
+
+// Declares a signal on a node class, e.g: `died = Signal(int)`
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct Signal {
+    #[pyo3(get)]
+    pub arg_types: Vec<Py<PyType>>,
+}
+
+#[pymethods]
+impl Signal {
+    #[new]
+    #[pyo3(signature = (*arg_types))]
+    pub fn new(arg_types: &PyTuple) -> PyResult<Self> {
+        Ok(Self {
+            arg_types: arg_types.extract()?,
+        })
+    }
+    pub fn __repr__(&self, py: Python) -> PyResult<String> {
+        let names = self
+            .arg_types
+            .iter()
+            .map(|arg_type| Ok(arg_type.as_ref(py).name()?.to_owned()))
+            .collect::<PyResult<Vec<String>>>()?;
+        Ok(format!("Signal({})", names.join(", ")))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Connection {
+    pub callable: PyObject,
+    // The node a bound method belongs to, so the connection can be dropped once it is freed
+    pub target: Option<PyObject>,
+    pub flags: u8,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SignalSlot {
+    pub arg_types: Vec<Py<PyType>>,
+    pub connections: Vec<Connection>,
+}
+
+impl SignalSlot {
+    pub fn new(arg_types: Vec<Py<PyType>>) -> Self {
+        Self {
+            arg_types,
+            connections: Vec::new(),
+        }
+    }
+    pub fn check_args(&self, py: Python, name: &str, args: &PyTuple) -> PyResult<()> {
+        if args.len() != self.arg_types.len() {
+            return Err(PyTypeError::new_err(format!(
+                "The signal '{name}' takes {0} argument(s) but {1} were given",
+                self.arg_types.len(),
+                args.len()
+            )));
+        }
+        for (arg, arg_type) in args.iter().zip(&self.arg_types) {
+            if !arg.is_instance(arg_type.as_ref(py))? {
+                return Err(PyTypeError::new_err(format!(
+                    "The signal '{name}' expected an argument of type '{0}' but got '{1}'",
+                    arg_type.as_ref(py).name()?,
+                    arg.get_type().name()?
+                )));
+            }
+        }
+        Ok(())
+    }
+    pub fn traverse(&self, visit: &PyVisit) -> Result<(), PyTraverseError> {
+        for connection in &self.connections {
+            visit.call(&connection.callable)?;
+            if let Some(target) = &connection.target {
+                visit.call(target)?;
+            }
+        }
+        Ok(())
+    }
+}
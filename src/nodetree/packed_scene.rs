@@ -0,0 +1,320 @@
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple, PyType};
+use std::{cell::RefCell, collections::HashMap};
+
+use super::node::*;
+use crate::builtin_types::all::*;
+
+const FORMAT_VERSION: u32 = 1;
+
+thread_local! {
+    // The python node classes that scenes can create, by class path.
+    // Every subclass of Node registers itself when it's defined
+    static REGISTERED_CLASSES: RefCell<HashMap<String, Py<PyType>>> = RefCell::new(HashMap::new());
+}
+
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct PackedScene {
+    // The packed nodes as json compatible python objects
+    data: PyObject,
+}
+
+#[pymethods]
+impl PackedScene {
+    #[staticmethod]
+    pub fn pack(py: Python, node: &PyCell<Node>) -> PyResult<Self> {
+        node.borrow().ensure_valid()?;
+        Ok(Self {
+            data: PackedScene::pack_node(py, node)?,
+        })
+    }
+    // Creates a new copy of the packed nodes, which can then be added to a tree
+    pub fn instantiate(&self, py: Python) -> PyResult<PyObject> {
+        PackedScene::instantiate_node(py, self.data.as_ref(py), None)
+    }
+    pub fn to_json(&self, py: Python) -> PyResult<String> {
+        let scene = PyDict::new(py);
+        scene.set_item("format", FORMAT_VERSION)?;
+        scene.set_item("root", &self.data)?;
+        py.import("json")?
+            .call_method("dumps", (scene,), Some([("indent", 4)].into_py_dict(py)))?
+            .extract()
+    }
+    #[staticmethod]
+    pub fn from_json(py: Python, text: &str) -> PyResult<Self> {
+        let scene = py.import("json")?.call_method1("loads", (text,))?;
+        let format: u32 = scene.get_item("format")?.extract()?;
+        if format != FORMAT_VERSION {
+            return Err(PyValueError::new_err(format!(
+                "Unsupported scene format version {format}"
+            )));
+        }
+        Ok(Self {
+            data: scene.get_item("root")?.into(),
+        })
+    }
+    pub fn save(&self, py: Python, path: &str) -> PyResult<()> {
+        Ok(std::fs::write(path, self.to_json(py)?)?)
+    }
+    #[staticmethod]
+    pub fn load(py: Python, path: &str) -> PyResult<Self> {
+        PackedScene::from_json(py, &std::fs::read_to_string(path)?)
+    }
+    pub fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "PackedScene({0})",
+            self.data.as_ref(py).get_item("type")?
+        ))
+    }
+}
+
+impl PackedScene {
+    fn pack_node(py: Python, node: &PyCell<Node>) -> PyResult<PyObject> {
+        let data = PyDict::new(py);
+        data.set_item("type", class_path(node.get_type())?)?;
+        data.set_item("name", node.borrow().get_name()?)?;
+        let properties = PyDict::new(py);
+        for property in exported_properties(node.get_type())? {
+            properties.set_item(
+                &property,
+                encode_value(py, node.getattr(property.as_str())?)?,
+            )?;
+        }
+        data.set_item("properties", properties)?;
+        data.set_item("groups", node.borrow().get_groups()?)?;
+        let children = node
+            .borrow()
+            .get_children()?
+            .iter()
+            .map(|child| PackedScene::pack_node(py, as_node(py, child)?))
+            .collect::<PyResult<Vec<PyObject>>>()?;
+        data.set_item("children", children)?;
+        Ok(data.into())
+    }
+    fn instantiate_node(
+        py: Python,
+        data: &PyAny,
+        parent: Option<&PyCell<Node>>,
+    ) -> PyResult<PyObject> {
+        let name: String = data.get_item("name")?.extract()?;
+        // Children that the parent already created in its constructor are reused rather than duplicated
+        let existing = match parent {
+            Some(parent) if !name.is_empty() => Node::get_child_by_name(parent, py, &name)?,
+            _ => None,
+        };
+        let node: PyObject = match existing {
+            Some(node) => node,
+            None => {
+                let node = load_class(py, data.get_item("type")?.extract()?)?.call0()?;
+                let cell: &PyCell<Node> = node.downcast()?;
+                if !name.is_empty() {
+                    Node::set_name(cell, py, name)?;
+                }
+                if let Some(parent) = parent {
+                    Node::add_child(parent, py, cell)?;
+                }
+                node.into()
+            }
+        };
+        let cell = as_node(py, &node)?;
+        for (property, value) in data.get_item("properties")?.downcast::<PyDict>()? {
+            node.setattr(py, property.extract::<&str>()?, decode_value(py, value)?)?;
+        }
+        for group in data.get_item("groups")?.extract::<Vec<String>>()? {
+            Node::add_to_group(cell, py, group)?;
+        }
+        for child in data.get_item("children")?.iter()? {
+            PackedScene::instantiate_node(py, child?, Some(cell))?;
+        }
+        Ok(node)
+    }
+}
+
+// The properties listed in the `__export__` attribute of the class and all of its bases
+fn exported_properties(class: &PyType) -> PyResult<Vec<String>> {
+    let mut properties: Vec<String> = Vec::new();
+    let mut bases = class.getattr("__mro__")?.extract::<Vec<&PyType>>()?;
+    bases.reverse();
+    for base in bases {
+        if let Some(export) = base
+            .getattr("__dict__")?
+            .call_method1("get", ("__export__",))?
+            .extract::<Option<Vec<String>>>()?
+        {
+            for property in export {
+                if !properties.contains(&property) {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+    Ok(properties)
+}
+
+// e.g: "__main__:Player" or "ferrokrait:Node2D"
+fn class_path(class: &PyType) -> PyResult<String> {
+    let module: &str = class.getattr("__module__")?.extract()?;
+    let qualname: &str = class.getattr("__qualname__")?.extract()?;
+    if qualname.contains("<locals>") {
+        return Err(PyTypeError::new_err(format!(
+            "The class '{qualname}' is defined inside a function and can't be packed"
+        )));
+    }
+    let module = if module == "builtins" {
+        "ferrokrait"
+    } else {
+        module
+    };
+    Ok(format!("{module}:{qualname}"))
+}
+
+// Classes defined inside functions can't be loaded by path, so they aren't registered
+pub fn register_class(class: &PyType) {
+    if let Ok(path) = class_path(class) {
+        REGISTERED_CLASSES.with(|classes| classes.borrow_mut().insert(path, class.into()));
+    }
+}
+
+// Only ferrokrait's own nodes and registered classes can be created, so loading a scene
+// never imports modules or calls anything that isn't a node class
+fn load_class<'py>(py: Python<'py>, path: &str) -> PyResult<&'py PyType> {
+    let (module, qualname) = path
+        .split_once(':')
+        .ok_or_else(|| PyValueError::new_err(format!("'{path}' is not a valid class path")))?;
+    let registered = REGISTERED_CLASSES.with(|classes| classes.borrow().get(path).cloned());
+    let class = match registered {
+        Some(class) => class.into_ref(py),
+        None if module == "ferrokrait" => py
+            .import(module)?
+            .getattr(qualname)
+            .ok()
+            .and_then(|class| class.downcast::<PyType>().ok())
+            .ok_or_else(|| {
+                PyValueError::new_err(format!("ferrokrait has no class '{qualname}'"))
+            })?,
+        None => {
+            return Err(PyValueError::new_err(format!(
+                "The class '{path}' isn't defined, import its module before loading the scene"
+            )))
+        }
+    };
+    if !class.is_subclass_of::<Node>()? {
+        return Err(PyTypeError::new_err(format!(
+            "'{path}' is not a node class"
+        )));
+    }
+    Ok(class)
+}
+
+fn encode_value(py: Python, value: &PyAny) -> PyResult<PyObject> {
+    let tagged = |name: &str, values: Vec<PyObject>| -> PyResult<PyObject> {
+        let data = PyDict::new(py);
+        data.set_item("__type__", name)?;
+        data.set_item("value", values)?;
+        Ok(data.into())
+    };
+    if value.is_none()
+        || value.is_instance_of::<PyBool>()?
+        || value.is_instance_of::<PyLong>()?
+        || value.is_instance_of::<PyFloat>()?
+        || value.is_instance_of::<PyString>()?
+    {
+        Ok(value.into())
+    } else if let Ok(vec) = value.extract::<Vec2>() {
+        tagged("Vec2", vec![vec.x.into_py(py), vec.y.into_py(py)])
+    } else if let Ok(vec) = value.extract::<Vec3>() {
+        tagged(
+            "Vec3",
+            vec![vec.x.into_py(py), vec.y.into_py(py), vec.z.into_py(py)],
+        )
+    } else if let Ok(vec) = value.extract::<Vec4>() {
+        tagged(
+            "Vec4",
+            vec![
+                vec.x.into_py(py),
+                vec.y.into_py(py),
+                vec.z.into_py(py),
+                vec.w.into_py(py),
+            ],
+        )
+    } else if let Ok(color) = value.downcast::<PyCell<Color>>() {
+        let color = color.borrow();
+        tagged(
+            "Color",
+            vec![
+                color.r.into_py(py),
+                color.g.into_py(py),
+                color.b.into_py(py),
+                color.a.into_py(py),
+            ],
+        )
+    } else if value.is_instance_of::<PyList>()? || value.is_instance_of::<PyTuple>()? {
+        let items = value
+            .iter()?
+            .map(|item| encode_value(py, item?))
+            .collect::<PyResult<Vec<PyObject>>>()?;
+        Ok(items.into_py(py))
+    } else if let Ok(dict) = value.downcast::<PyDict>() {
+        let encoded = PyDict::new(py);
+        for (key, item) in dict {
+            encoded.set_item(key.extract::<&str>()?, encode_value(py, item)?)?;
+        }
+        Ok(encoded.into())
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "Values of type '{0}' can't be saved in a scene",
+            value.get_type().name()?
+        )))
+    }
+}
+
+fn decode_value(py: Python, value: &PyAny) -> PyResult<PyObject> {
+    if let Ok(dict) = value.downcast::<PyDict>() {
+        if let Some(name) = dict.get_item("__type__") {
+            let values: Vec<f64> = dict
+                .get_item("value")
+                .ok_or_else(|| PyValueError::new_err("Tagged value is missing its data"))?
+                .extract()?;
+            return match (name.extract::<&str>()?, values.as_slice()) {
+                ("Vec2", &[x, y]) => Ok(Vec2::new(x, y).into_py(py)),
+                ("Vec3", &[x, y, z]) => Ok(Vec3::new(x, y, z).into_py(py)),
+                ("Vec4", &[x, y, z, w]) => Ok(Vec4::new(x, y, z, w).into_py(py)),
+                ("Color", &[r, g, b, a]) => {
+                    let channel = |value: f64| {
+                        if value.fract() != 0.0 || !(0.0..=255.0).contains(&value) {
+                            return Err(PyValueError::new_err(format!(
+                                "Color channels must be whole numbers from 0 to 255, not {value}"
+                            )));
+                        }
+                        Ok(value as u8)
+                    };
+                    let color = Color {
+                        r: channel(r)?,
+                        g: channel(g)?,
+                        b: channel(b)?,
+                        a: channel(a)?,
+                    };
+                    Ok(Py::new(py, color)?.into_py(py))
+                }
+                (name, _) => Err(PyValueError::new_err(format!(
+                    "Unknown or malformed value of type '{name}'"
+                ))),
+            };
+        }
+        let decoded = PyDict::new(py);
+        for (key, item) in dict {
+            decoded.set_item(key, decode_value(py, item)?)?;
+        }
+        Ok(decoded.into())
+    } else if let Ok(list) = value.downcast::<PyList>() {
+        let items = list
+            .iter()
+            .map(|item| decode_value(py, item))
+            .collect::<PyResult<Vec<PyObject>>>()?;
+        Ok(items.into_py(py))
+    } else {
+        Ok(value.into())
+    }
+}
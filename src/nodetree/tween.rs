@@ -0,0 +1,646 @@
+use pyo3::prelude::*;
+use pyo3::types::PyLong;
+use pyo3::{
+    exceptions::{PyTypeError, PyValueError},
+    PyTraverseError, PyVisit,
+};
+use std::f64::consts::PI;
+
+use super::{node::*, NodeTree};
+use crate::builtin_types::all::*;
+
+// Animates attributes of any object over time, stepped by the tree once per frame
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct Tween {
+    // Each step is a group of tweeners that run in parallel, the steps run one after another
+    steps: Vec<Vec<Py<Tweener>>>,
+    current_step: usize,
+    // 0 loops forever
+    loops: u32,
+    loops_done: u32,
+    // Overrides `default_parallel` for the next tweener only
+    parallel_next: Option<bool>,
+    default_parallel: bool,
+    default_trans: u8,
+    default_ease: u8,
+    // The tween stops with this node when it is freed and pauses when it can't process
+    pub(crate) bound_node: Option<PyObject>,
+    running: bool,
+    valid: bool,
+    // Called without arguments once every loop has finished
+    #[pyo3(get, set)]
+    on_finished: Option<PyObject>,
+}
+
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct Tweener {
+    kind: TweenerKind,
+    duration: f64,
+    delay: f64,
+    trans: u8,
+    ease: u8,
+    relative: bool,
+    from: Option<TweenValue>,
+    // Captured when the tweener starts so the tween picks up changes made before then
+    initial: Option<TweenValue>,
+    target: Option<TweenValue>,
+    elapsed: f64,
+    finished: bool,
+}
+
+#[derive(Clone, Debug)]
+enum TweenerKind {
+    Property {
+        object: PyObject,
+        property: String,
+        to: TweenValue,
+    },
+    Interval,
+    Callback {
+        callable: PyObject,
+    },
+}
+
+#[derive(Clone, Copy, Debug)]
+enum TweenValue {
+    Int(i64),
+    Float(f64),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Color(Color),
+}
+
+#[pymethods]
+impl Tween {
+    #[classattr]
+    pub const TRANS_LINEAR: u8 = 0;
+    #[classattr]
+    pub const TRANS_SINE: u8 = 1;
+    #[classattr]
+    pub const TRANS_QUAD: u8 = 2;
+    #[classattr]
+    pub const TRANS_CUBIC: u8 = 3;
+    #[classattr]
+    pub const TRANS_EXPO: u8 = 4;
+    #[classattr]
+    pub const TRANS_ELASTIC: u8 = 5;
+    #[classattr]
+    pub const TRANS_BOUNCE: u8 = 6;
+    #[classattr]
+    pub const TRANS_BACK: u8 = 7;
+    #[classattr]
+    pub const EASE_IN: u8 = 0;
+    #[classattr]
+    pub const EASE_OUT: u8 = 1;
+    #[classattr]
+    pub const EASE_IN_OUT: u8 = 2;
+
+    // Animates `object.property` to `final_val`, "position:x" animates a single component
+    pub fn tween_property(
+        slf: &PyCell<Self>,
+        py: Python,
+        object: PyObject,
+        property: String,
+        final_val: &PyAny,
+        duration: f64,
+    ) -> PyResult<Py<Tweener>> {
+        let to = TweenValue::extract(final_val)?;
+        TweenValue::extract(get_property(object.as_ref(py), &property)?)?;
+        let kind = TweenerKind::Property {
+            object,
+            property,
+            to,
+        };
+        Tween::append(slf, py, kind, duration)
+    }
+    pub fn tween_interval(slf: &PyCell<Self>, py: Python, time: f64) -> PyResult<Py<Tweener>> {
+        Tween::append(slf, py, TweenerKind::Interval, time)
+    }
+    pub fn tween_callback(
+        slf: &PyCell<Self>,
+        py: Python,
+        callable: PyObject,
+    ) -> PyResult<Py<Tweener>> {
+        if !callable.as_ref(py).is_callable() {
+            return Err(PyTypeError::new_err("Tween callbacks must be callable!"));
+        }
+        Tween::append(slf, py, TweenerKind::Callback { callable }, 0.0)
+    }
+    // Runs the next tweener alongside the previous one
+    pub fn parallel(slf: Py<Self>, py: Python) -> Py<Self> {
+        slf.borrow_mut(py).parallel_next = Some(true);
+        slf
+    }
+    // Runs the next tweener after the previous one, even when the tween is parallel
+    pub fn chain(slf: Py<Self>, py: Python) -> Py<Self> {
+        slf.borrow_mut(py).parallel_next = Some(false);
+        slf
+    }
+    #[pyo3(signature = (parallel=true))]
+    pub fn set_parallel(slf: Py<Self>, py: Python, parallel: bool) -> Py<Self> {
+        slf.borrow_mut(py).default_parallel = parallel;
+        slf
+    }
+    #[pyo3(signature = (loops=0))]
+    pub fn set_loops(slf: Py<Self>, py: Python, loops: u32) -> Py<Self> {
+        slf.borrow_mut(py).loops = loops;
+        slf
+    }
+    // The default transition of tweeners added afterwards
+    pub fn set_trans(slf: Py<Self>, py: Python, trans: u8) -> PyResult<Py<Self>> {
+        slf.borrow_mut(py).default_trans = check_trans(trans)?;
+        Ok(slf)
+    }
+    pub fn set_ease(slf: Py<Self>, py: Python, ease: u8) -> PyResult<Py<Self>> {
+        slf.borrow_mut(py).default_ease = check_ease(ease)?;
+        Ok(slf)
+    }
+    pub fn play(&mut self) {
+        self.running = true;
+    }
+    pub fn pause(&mut self) {
+        self.running = false;
+    }
+    // Stops the tween for good, without calling `on_finished`
+    pub fn kill(&mut self) {
+        self.valid = false;
+    }
+    pub fn is_running(&self) -> bool {
+        self.valid && self.running
+    }
+    pub fn is_valid(&self) -> bool {
+        self.valid
+    }
+    pub fn get_loops_left(&self) -> i64 {
+        if self.loops == 0 {
+            -1
+        } else {
+            (self.loops - self.loops_done.min(self.loops)) as i64
+        }
+    }
+    // Steps the tween manually, returns whether it is still valid
+    pub fn custom_step(slf: &PyCell<Self>, py: Python, delta: f64) -> PyResult<bool> {
+        Tween::step(slf, py, delta)
+    }
+    #[staticmethod]
+    #[pyo3(name = "ease")]
+    pub fn ease_py(t: f64, trans: u8, ease: u8) -> PyResult<f64> {
+        Ok(interpolate_ease(check_trans(trans)?, check_ease(ease)?, t))
+    }
+    pub fn __repr__(&self) -> String {
+        format!(
+            "Tween(steps: {}, running: {})",
+            self.steps.len(),
+            self.is_running()
+        )
+    }
+    pub fn __traverse__(&self, visit: PyVisit) -> Result<(), PyTraverseError> {
+        for tweener in self.steps.iter().flatten() {
+            visit.call(tweener)?;
+        }
+        if let Some(node) = &self.bound_node {
+            visit.call(node)?;
+        }
+        if let Some(on_finished) = &self.on_finished {
+            visit.call(on_finished)?;
+        }
+        Ok(())
+    }
+    pub fn __clear__(&mut self) {
+        self.steps.clear();
+        self.bound_node = None;
+        self.on_finished = None;
+    }
+}
+
+impl Tween {
+    // Creates a tween that the tree steps until it finishes or is killed
+    pub fn create(
+        py: Python,
+        tree: &Py<NodeTree>,
+        bound_node: Option<PyObject>,
+    ) -> PyResult<Py<Tween>> {
+        let tween = Py::new(
+            py,
+            Tween {
+                steps: Vec::new(),
+                current_step: 0,
+                loops: 1,
+                loops_done: 0,
+                parallel_next: None,
+                default_parallel: false,
+                default_trans: Tween::TRANS_LINEAR,
+                default_ease: Tween::EASE_IN_OUT,
+                bound_node,
+                running: true,
+                valid: true,
+                on_finished: None,
+            },
+        )?;
+        tree.borrow_mut(py).tweens.push(tween.clone_ref(py));
+        Ok(tween)
+    }
+    fn append(
+        slf: &PyCell<Self>,
+        py: Python,
+        kind: TweenerKind,
+        duration: f64,
+    ) -> PyResult<Py<Tweener>> {
+        if duration < 0.0 {
+            return Err(PyValueError::new_err(
+                "Tweeners can't have a negative duration!",
+            ));
+        }
+        let mut tween = slf.borrow_mut();
+        let tweener = Py::new(
+            py,
+            Tweener {
+                kind,
+                duration,
+                delay: 0.0,
+                trans: tween.default_trans,
+                ease: tween.default_ease,
+                relative: false,
+                from: None,
+                initial: None,
+                target: None,
+                elapsed: 0.0,
+                finished: false,
+            },
+        )?;
+        let parallel = tween.parallel_next.take().unwrap_or(tween.default_parallel);
+        match tween.steps.last_mut() {
+            Some(step) if parallel => step.push(tweener.clone_ref(py)),
+            _ => tween.steps.push(vec![tweener.clone_ref(py)]),
+        }
+        Ok(tweener)
+    }
+    // Advances the tween by `delta` seconds, returns whether it should keep being stepped
+    pub fn step(slf: &PyCell<Self>, py: Python, delta: f64) -> PyResult<bool> {
+        let bound_node = {
+            let tween = slf.borrow();
+            if !tween.valid {
+                return Ok(false);
+            }
+            if !tween.running {
+                return Ok(true);
+            }
+            tween.bound_node.clone()
+        };
+        if let Some(node) = bound_node {
+            let node = as_node(py, &node)?;
+            if node.borrow().is_freed() {
+                slf.borrow_mut().valid = false;
+                return Ok(false);
+            }
+            if !Node::can_process(node, py)? {
+                return Ok(true);
+            }
+        }
+        if slf.borrow().steps.is_empty() {
+            slf.borrow_mut().valid = false;
+            return Ok(false);
+        }
+        let mut remaining = delta;
+        let mut loop_start = remaining;
+        loop {
+            let step = {
+                let tween = slf.borrow();
+                tween.steps[tween.current_step].clone()
+            };
+            let mut leftover = f64::INFINITY;
+            let mut done = true;
+            for tweener in &step {
+                match Tweener::advance(tweener.as_ref(py), py, remaining)? {
+                    Some(rest) => leftover = leftover.min(rest),
+                    None => done = false,
+                }
+                // A callback may have killed the tween
+                if !slf.borrow().valid {
+                    return Ok(false);
+                }
+            }
+            if !done {
+                return Ok(true);
+            }
+            remaining = leftover;
+            let mut tween = slf.borrow_mut();
+            tween.current_step += 1;
+            if tween.current_step < tween.steps.len() {
+                continue;
+            }
+            tween.loops_done += 1;
+            if tween.loops != 0 && tween.loops_done >= tween.loops {
+                tween.valid = false;
+                let on_finished = tween.on_finished.clone();
+                drop(tween);
+                if let Some(on_finished) = on_finished {
+                    on_finished.call0(py)?;
+                }
+                return Ok(false);
+            }
+            tween.current_step = 0;
+            for tweener in tween.steps.iter().flatten() {
+                tweener.borrow_mut(py).reset();
+            }
+            // A loop that takes no time would otherwise repeat forever within a single step
+            if remaining >= loop_start {
+                return Ok(true);
+            }
+            loop_start = remaining;
+        }
+    }
+}
+
+#[pymethods]
+impl Tweener {
+    pub fn set_trans(slf: Py<Self>, py: Python, trans: u8) -> PyResult<Py<Self>> {
+        slf.borrow_mut(py).trans = check_trans(trans)?;
+        Ok(slf)
+    }
+    pub fn set_ease(slf: Py<Self>, py: Python, ease: u8) -> PyResult<Py<Self>> {
+        slf.borrow_mut(py).ease = check_ease(ease)?;
+        Ok(slf)
+    }
+    pub fn set_delay(slf: Py<Self>, py: Python, delay: f64) -> PyResult<Py<Self>> {
+        if delay < 0.0 {
+            return Err(PyValueError::new_err(
+                "Tweeners can't have a negative delay!",
+            ));
+        }
+        slf.borrow_mut(py).delay = delay;
+        Ok(slf)
+    }
+    // Starts a property tweener from the given value instead of the current one
+    #[pyo3(name = "from_")]
+    pub fn from_value(slf: Py<Self>, py: Python, value: &PyAny) -> PyResult<Py<Self>> {
+        slf.borrow_mut(py).from = Some(TweenValue::extract(value)?);
+        Ok(slf)
+    }
+    // Treats the final value of a property tweener as an offset from the initial one
+    pub fn as_relative(slf: Py<Self>, py: Python) -> Py<Self> {
+        slf.borrow_mut(py).relative = true;
+        slf
+    }
+    pub fn __traverse__(&self, visit: PyVisit) -> Result<(), PyTraverseError> {
+        match &self.kind {
+            TweenerKind::Property { object, .. } => visit.call(object),
+            TweenerKind::Callback { callable } => visit.call(callable),
+            TweenerKind::Interval => Ok(()),
+        }
+    }
+    pub fn __clear__(&mut self) {
+        self.kind = TweenerKind::Interval;
+    }
+}
+
+impl Tweener {
+    // Returns the time left over from `delta` once the tweener has finished, or None while it runs
+    fn advance(slf: &PyCell<Self>, py: Python, delta: f64) -> PyResult<Option<f64>> {
+        let (kind, time) = {
+            let mut tweener = slf.borrow_mut();
+            if tweener.finished {
+                return Ok(Some(delta));
+            }
+            tweener.elapsed += delta;
+            if tweener.elapsed < tweener.delay {
+                return Ok(None);
+            }
+            (tweener.kind.clone(), tweener.elapsed - tweener.delay)
+        };
+        match kind {
+            TweenerKind::Interval => {
+                let mut tweener = slf.borrow_mut();
+                tweener.finished = time >= tweener.duration;
+                Ok(tweener.finished.then_some(time - tweener.duration))
+            }
+            TweenerKind::Callback { callable } => {
+                slf.borrow_mut().finished = true;
+                callable.call0(py)?;
+                Ok(Some(time))
+            }
+            TweenerKind::Property {
+                object,
+                property,
+                to,
+            } => {
+                if let Ok(node) = as_node(py, &object) {
+                    if node.borrow().is_freed() {
+                        slf.borrow_mut().finished = true;
+                        return Ok(Some(time));
+                    }
+                }
+                if slf.borrow().initial.is_none() {
+                    let current = TweenValue::extract(get_property(object.as_ref(py), &property)?)?;
+                    let mut tweener = slf.borrow_mut();
+                    let initial = tweener.from.unwrap_or(current);
+                    tweener.target = Some(if tweener.relative {
+                        initial.add(&to)?
+                    } else {
+                        to
+                    });
+                    tweener.initial = Some(initial);
+                }
+                let value = {
+                    let mut tweener = slf.borrow_mut();
+                    let weight = if tweener.duration <= 0.0 {
+                        1.0
+                    } else {
+                        (time / tweener.duration).min(1.0)
+                    };
+                    tweener.finished = time >= tweener.duration;
+                    let (initial, target) = (tweener.initial.unwrap(), tweener.target.unwrap());
+                    initial.interpolate(
+                        &target,
+                        interpolate_ease(tweener.trans, tweener.ease, weight),
+                    )?
+                };
+                set_property(object.as_ref(py), &property, value.into_py(py))?;
+                let tweener = slf.borrow();
+                Ok(tweener.finished.then_some(time - tweener.duration))
+            }
+        }
+    }
+    fn reset(&mut self) {
+        self.initial = None;
+        self.target = None;
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+}
+
+impl TweenValue {
+    fn extract(value: &PyAny) -> PyResult<Self> {
+        if let Ok(value) = value.extract::<Vec2>() {
+            Ok(TweenValue::Vec2(value))
+        } else if let Ok(value) = value.extract::<Vec3>() {
+            Ok(TweenValue::Vec3(value))
+        } else if let Ok(value) = value.extract::<Vec4>() {
+            Ok(TweenValue::Vec4(value))
+        } else if let Ok(value) = value.extract::<Color>() {
+            Ok(TweenValue::Color(value))
+        } else if value.is_instance_of::<PyLong>()? {
+            Ok(TweenValue::Int(value.extract()?))
+        } else if let Ok(value) = value.extract::<f64>() {
+            Ok(TweenValue::Float(value))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "Values of type '{}' can't be tweened",
+                value.get_type().name()?
+            )))
+        }
+    }
+    fn interpolate(&self, to: &Self, weight: f64) -> PyResult<Self> {
+        Ok(match (*self, *to) {
+            (TweenValue::Int(from), TweenValue::Int(to)) => {
+                TweenValue::Int((from as f64).lerp(to as f64, weight).round() as i64)
+            }
+            (TweenValue::Int(from), TweenValue::Float(to)) => {
+                TweenValue::Float((from as f64).lerp(to, weight))
+            }
+            (TweenValue::Float(from), TweenValue::Int(to)) => {
+                TweenValue::Float(from.lerp(to as f64, weight))
+            }
+            (TweenValue::Float(from), TweenValue::Float(to)) => {
+                TweenValue::Float(from.lerp(to, weight))
+            }
+            (TweenValue::Vec2(from), TweenValue::Vec2(to)) => {
+                TweenValue::Vec2((&from).lerp(&to, weight))
+            }
+            (TweenValue::Vec3(from), TweenValue::Vec3(to)) => {
+                TweenValue::Vec3((&from).lerp(&to, weight))
+            }
+            (TweenValue::Vec4(from), TweenValue::Vec4(to)) => {
+                TweenValue::Vec4((&from).lerp(&to, weight))
+            }
+            (TweenValue::Color(from), TweenValue::Color(to)) => {
+                TweenValue::Color((&from).lerp(&to, weight))
+            }
+            _ => return Err(self.mismatch(to)),
+        })
+    }
+    fn add(&self, rhs: &Self) -> PyResult<Self> {
+        Ok(match (*self, *rhs) {
+            (TweenValue::Int(lhs), TweenValue::Int(rhs)) => TweenValue::Int(lhs + rhs),
+            (TweenValue::Int(lhs), TweenValue::Float(rhs)) => TweenValue::Float(lhs as f64 + rhs),
+            (TweenValue::Float(lhs), TweenValue::Int(rhs)) => TweenValue::Float(lhs + rhs as f64),
+            (TweenValue::Float(lhs), TweenValue::Float(rhs)) => TweenValue::Float(lhs + rhs),
+            (TweenValue::Vec2(lhs), TweenValue::Vec2(rhs)) => TweenValue::Vec2(lhs + rhs),
+            (TweenValue::Vec3(lhs), TweenValue::Vec3(rhs)) => TweenValue::Vec3(lhs + rhs),
+            (TweenValue::Vec4(lhs), TweenValue::Vec4(rhs)) => TweenValue::Vec4(lhs + rhs),
+            _ => return Err(self.mismatch(rhs)),
+        })
+    }
+    fn mismatch(&self, other: &Self) -> PyErr {
+        PyTypeError::new_err(format!(
+            "Can't tween between a {} and a {}",
+            self.type_name(),
+            other.type_name()
+        ))
+    }
+    const fn type_name(&self) -> &'static str {
+        match self {
+            TweenValue::Int(_) => "int",
+            TweenValue::Float(_) => "float",
+            TweenValue::Vec2(_) => "Vec2",
+            TweenValue::Vec3(_) => "Vec3",
+            TweenValue::Vec4(_) => "Vec4",
+            TweenValue::Color(_) => "Color",
+        }
+    }
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            TweenValue::Int(value) => value.into_py(py),
+            TweenValue::Float(value) => value.into_py(py),
+            TweenValue::Vec2(value) => value.into_py(py),
+            TweenValue::Vec3(value) => value.into_py(py),
+            TweenValue::Vec4(value) => value.into_py(py),
+            TweenValue::Color(value) => value.into_py(py),
+        }
+    }
+}
+
+fn get_property<'py>(object: &'py PyAny, property: &str) -> PyResult<&'py PyAny> {
+    match property.split_once(':') {
+        Some((attribute, component)) => object.getattr(attribute)?.getattr(component),
+        None => object.getattr(property),
+    }
+}
+
+// Components are set on a copy of the attribute, which is then assigned back
+fn set_property(object: &PyAny, property: &str, value: PyObject) -> PyResult<()> {
+    match property.split_once(':') {
+        Some((attribute, component)) => {
+            let attribute_value = object.getattr(attribute)?;
+            attribute_value.setattr(component, value)?;
+            object.setattr(attribute, attribute_value)
+        }
+        None => object.setattr(property, value),
+    }
+}
+
+fn check_trans(trans: u8) -> PyResult<u8> {
+    if trans > Tween::TRANS_BACK {
+        return Err(PyValueError::new_err(format!(
+            "{trans} is not a valid transition type"
+        )));
+    }
+    Ok(trans)
+}
+
+fn check_ease(ease: u8) -> PyResult<u8> {
+    if ease > Tween::EASE_IN_OUT {
+        return Err(PyValueError::new_err(format!(
+            "{ease} is not a valid ease type"
+        )));
+    }
+    Ok(ease)
+}
+
+// Maps a progress from 0 to 1 onto the given curve
+pub fn interpolate_ease(trans: u8, ease: u8, t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    match ease {
+        Tween::EASE_IN => ease_in(trans, t),
+        Tween::EASE_OUT => 1.0 - ease_in(trans, 1.0 - t),
+        _ if t < 0.5 => ease_in(trans, t * 2.0) / 2.0,
+        _ => 1.0 - ease_in(trans, 2.0 - t * 2.0) / 2.0,
+    }
+}
+
+fn ease_in(trans: u8, t: f64) -> f64 {
+    match trans {
+        Tween::TRANS_SINE => 1.0 - (t * PI / 2.0).cos(),
+        Tween::TRANS_QUAD => t * t,
+        Tween::TRANS_CUBIC => t * t * t,
+        Tween::TRANS_EXPO if t == 0.0 => 0.0,
+        Tween::TRANS_EXPO => 2f64.powf(10.0 * t - 10.0),
+        Tween::TRANS_ELASTIC if t == 0.0 || t == 1.0 => t,
+        Tween::TRANS_ELASTIC => {
+            -(2f64.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI / 3.0)).sin()
+        }
+        Tween::TRANS_BOUNCE => 1.0 - bounce_out(1.0 - t),
+        Tween::TRANS_BACK => 2.70158 * t * t * t - 1.70158 * t * t,
+        _ => t,
+    }
+}
+
+fn bounce_out(t: f64) -> f64 {
+    const N: f64 = 7.5625;
+    const D: f64 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
@@ -0,0 +1,175 @@
+use pyo3::prelude::*;
+use std::f64::consts::TAU;
+
+use super::{font, framebuffer::*};
+use crate::builtin_types::all::*;
+
+// A drawing operation recorded by a 2D node, in the node's local space
+#[derive(Clone, Debug)]
+pub enum DrawCommand {
+    Line {
+        start: Vec2,
+        end: Vec2,
+        color: Color,
+        width: f64,
+    },
+    Rect {
+        position: Vec2,
+        size: Vec2,
+        color: Color,
+        filled: bool,
+        width: f64,
+    },
+    Circle {
+        center: Vec2,
+        radius: f64,
+        color: Color,
+        filled: bool,
+        width: f64,
+    },
+    Polygon {
+        points: Vec<Vec2>,
+        color: Color,
+        filled: bool,
+        width: f64,
+    },
+    Texture {
+        texture: Py<Image>,
+        position: Vec2,
+        modulate: Color,
+    },
+    // Drawn with the built in bitmap font, `size` units per font pixel
+    String {
+        text: String,
+        position: Vec2,
+        color: Color,
+        size: f64,
+    },
+}
+
+impl DrawCommand {
+    // Draws the command into the framebuffer, moved from local space by the transform
+    pub fn draw(
+        &self,
+        py: Python,
+        target: &mut Framebuffer,
+        transform: &Transform2D,
+    ) -> PyResult<()> {
+        // Widths and radii are scaled by the average scale of the transform
+        let scale = transform.determinant().abs().sqrt();
+        let axis_aligned = transform.x.y == 0.0 && transform.y.x == 0.0;
+        match self {
+            DrawCommand::Line {
+                start,
+                end,
+                color,
+                width,
+            } => target.draw_line(
+                transform.xform(*start),
+                transform.xform(*end),
+                *color,
+                width * scale,
+            ),
+            DrawCommand::Rect {
+                position,
+                size,
+                color,
+                filled,
+                width,
+            } if axis_aligned => {
+                let start = transform.xform(*position);
+                target.draw_rect(
+                    start,
+                    transform.xform(*position + *size) - start,
+                    *color,
+                    *filled,
+                    width * scale,
+                )
+            }
+            DrawCommand::Rect {
+                position,
+                size,
+                color,
+                filled,
+                width,
+            } => target.draw_polygon(
+                rect_points(*position, *position + *size)
+                    .map(|point| transform.xform(point))
+                    .to_vec(),
+                *color,
+                *filled,
+                width * scale,
+            ),
+            DrawCommand::Circle {
+                center,
+                radius,
+                color,
+                filled,
+                width,
+            } if axis_aligned && transform.x.x.abs() == transform.y.y.abs() => target.draw_circle(
+                transform.xform(*center),
+                radius * scale,
+                *color,
+                *filled,
+                width * scale,
+            ),
+            // Rotated, skewed or stretched circles become polygons
+            DrawCommand::Circle {
+                center,
+                radius,
+                color,
+                filled,
+                width,
+            } => {
+                let segments = ((radius * scale) as usize).clamp(16, 128);
+                let points = (0..segments)
+                    .map(|index| {
+                        let angle = TAU * index as f64 / segments as f64;
+                        transform.xform(*center + Vec2::new(angle.cos(), angle.sin()) * *radius)
+                    })
+                    .collect();
+                target.draw_polygon(points, *color, *filled, width * scale)
+            }
+            DrawCommand::Polygon {
+                points,
+                color,
+                filled,
+                width,
+            } => target.draw_polygon(
+                points.iter().map(|point| transform.xform(*point)).collect(),
+                *color,
+                *filled,
+                width * scale,
+            ),
+            DrawCommand::Texture {
+                texture,
+                position,
+                modulate,
+            } => {
+                return target.draw_texture_transformed(
+                    &*texture.try_borrow(py)?,
+                    transform.compose(&Transform2D::IDENTITY.translated(*position)),
+                    *modulate,
+                )
+            }
+            DrawCommand::String {
+                text,
+                position,
+                color,
+                size,
+            } => {
+                for (x, y) in font::pixels(text) {
+                    let pixel = DrawCommand::Rect {
+                        position: *position + Vec2::new(x as f64, y as f64) * *size,
+                        size: Vec2::new(*size, *size),
+                        color: *color,
+                        filled: true,
+                        width: 1.0,
+                    };
+                    pixel.draw(py, target, transform)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
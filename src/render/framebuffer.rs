@@ -0,0 +1,375 @@
+use pyo3::prelude::*;
+use pyo3::{
+    exceptions::{PyIndexError, PyValueError},
+    types::PyBytes,
+};
+
+use crate::builtin_types::all::*;
+
+// An RGBA image that everything is rasterized into, usable without a window or GPU.
+// Pixel centers are at half coordinates, so a rect from (0, 0) to (2, 2) covers 4 pixels
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct Framebuffer {
+    #[pyo3(get)]
+    pub(crate) width: usize,
+    #[pyo3(get)]
+    pub(crate) height: usize,
+    pub(crate) pixels: Vec<Color>,
+}
+
+#[pymethods]
+impl Framebuffer {
+    #[new]
+    pub fn new(width: usize, height: usize) -> PyResult<Self> {
+        if width == 0 || height == 0 {
+            return Err(PyValueError::new_err(
+                "A framebuffer must be at least 1 pixel wide and tall",
+            ));
+        }
+        let count = Image::pixel_count(width, height)
+            .map_err(|_| PyValueError::new_err("The framebuffer is too large"))?;
+        Ok(Self {
+            width,
+            height,
+            pixels: vec![Color::TRANSPARENT; count],
+        })
+    }
+    #[pyo3(signature = (color = Color::BLACK))]
+    pub fn clear(&mut self, color: Color) {
+        self.pixels.fill(color);
+    }
+    pub fn get_pixel(&self, x: i64, y: i64) -> PyResult<Color> {
+        Ok(self.pixels[self.index(x, y)?])
+    }
+    // Replaces the pixel, unlike the drawing methods which blend with it
+    pub fn set_pixel(&mut self, x: i64, y: i64, color: Color) -> PyResult<()> {
+        let index = self.index(x, y)?;
+        self.pixels[index] = color;
+        Ok(())
+    }
+    pub fn blend_pixel(&mut self, x: i64, y: i64, color: Color) -> PyResult<()> {
+        let index = self.index(x, y)?;
+        self.pixels[index] = self.pixels[index].blend(color);
+        Ok(())
+    }
+    #[pyo3(signature = (start, end, color, width = 1.0))]
+    pub fn draw_line(&mut self, start: Vec2, end: Vec2, color: Color, width: f64) {
+        if width <= 1.0 {
+            self.line(start, end, color);
+        } else {
+            self.fill_polygon(&thick_line(start, end, width), color);
+        }
+    }
+    #[pyo3(signature = (position, size, color, filled = true, width = 1.0))]
+    pub fn draw_rect(
+        &mut self,
+        position: Vec2,
+        size: Vec2,
+        color: Color,
+        filled: bool,
+        width: f64,
+    ) {
+        let (start, end) = (position, position + size);
+        let (min, max) = (start.min(&end), start.max(&end));
+        if filled || width * 2.0 >= (max.x - min.x).min(max.y - min.y) {
+            self.fill_polygon(&rect_points(min, max), color);
+            return;
+        }
+        // Four strips that don't overlap, so translucent outlines don't blend twice in the corners
+        let (inner_min, inner_max) = (min + Vec2::new(width, width), max - Vec2::new(width, width));
+        self.fill_polygon(&rect_points(min, Vec2::new(max.x, inner_min.y)), color);
+        self.fill_polygon(&rect_points(Vec2::new(min.x, inner_max.y), max), color);
+        self.fill_polygon(
+            &rect_points(
+                Vec2::new(min.x, inner_min.y),
+                Vec2::new(inner_min.x, inner_max.y),
+            ),
+            color,
+        );
+        self.fill_polygon(
+            &rect_points(
+                Vec2::new(inner_max.x, inner_min.y),
+                Vec2::new(max.x, inner_max.y),
+            ),
+            color,
+        );
+    }
+    #[pyo3(signature = (center, radius, color, filled = true, width = 1.0))]
+    pub fn draw_circle(
+        &mut self,
+        center: Vec2,
+        radius: f64,
+        color: Color,
+        filled: bool,
+        width: f64,
+    ) {
+        let outer_squared = radius * radius;
+        let inner_squared = if filled || width >= radius {
+            -1.0
+        } else {
+            (radius - width) * (radius - width)
+        };
+        let (columns, rows) = self.clip(
+            center - Vec2::new(radius, radius),
+            center + Vec2::new(radius, radius),
+        );
+        for y in rows {
+            for x in columns.clone() {
+                let (dx, dy) = (x as f64 + 0.5 - center.x, y as f64 + 0.5 - center.y);
+                let distance_squared = dx * dx + dy * dy;
+                if distance_squared <= outer_squared && distance_squared > inner_squared {
+                    self.blend_clipped(x, y, color);
+                }
+            }
+        }
+    }
+    #[pyo3(signature = (points, color, filled = true, width = 1.0))]
+    pub fn draw_polygon(&mut self, points: Vec<Vec2>, color: Color, filled: bool, width: f64) {
+        if filled {
+            self.fill_polygon(&points, color);
+            return;
+        }
+        for (index, start) in points.iter().enumerate() {
+            let end = points[(index + 1) % points.len()];
+            self.draw_line(*start, end, color, width);
+        }
+    }
+    // Draws the texture with its top left corner at `position`, multiplying it by `modulate`
+    #[pyo3(signature = (texture, position, modulate = Color::WHITE))]
+    pub fn draw_texture(
+        &mut self,
+        texture: &Image,
+        position: Vec2,
+        modulate: Color,
+    ) -> PyResult<()> {
+        self.draw_texture_transformed(
+            texture,
+            Transform2D::IDENTITY.translated(position),
+            modulate,
+        )
+    }
+    // Draws the texture as a quad, mapping texture pixels through the transform
+    #[pyo3(signature = (texture, transform, modulate = Color::WHITE))]
+    pub fn draw_texture_transformed(
+        &mut self,
+        texture: &Image,
+        transform: Transform2D,
+        modulate: Color,
+    ) -> PyResult<()> {
+        let size = Vec2::new(texture.width as f64, texture.height as f64);
+        self.draw_texture_region(texture, Vec2::ZERO, size, transform, modulate)
+    }
+    // A copy of the current contents, to save or use as a texture
+    pub fn get_image(&self) -> Image {
+        Image::from_pixels(self.width, self.height, self.pixels.clone())
+    }
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        self.get_image().to_bytes(py)
+    }
+    pub fn save_png(&self, path: &str) -> PyResult<()> {
+        self.get_image().save_png(path)
+    }
+    pub fn save_ppm(&self, path: &str) -> PyResult<()> {
+        self.get_image().save_ppm(path)
+    }
+    pub fn __repr__(&self) -> String {
+        format!("Framebuffer({0}, {1})", self.width, self.height)
+    }
+}
+
+impl Framebuffer {
+    fn offset(&self, x: i64, y: i64) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        Some(y as usize * self.width + x as usize)
+    }
+    fn index(&self, x: i64, y: i64) -> PyResult<usize> {
+        self.offset(x, y).ok_or_else(|| {
+            PyIndexError::new_err(format!(
+                "The pixel ({x}, {y}) is outside of the {0}x{1} framebuffer",
+                self.width, self.height
+            ))
+        })
+    }
+    // The columns and rows of the pixels touching the area, limited to the framebuffer
+    fn clip(&self, min: Vec2, max: Vec2) -> (std::ops::Range<i64>, std::ops::Range<i64>) {
+        let range = |min: f64, max: f64, size: usize| {
+            (min.floor().max(0.0) as i64)..(max.ceil().min(size as f64) as i64)
+        };
+        (
+            range(min.x, max.x, self.width),
+            range(min.y, max.y, self.height),
+        )
+    }
+    // Draws part of the texture, with the transform mapping the region's top left corner to the origin
+    pub fn draw_texture_region(
+        &mut self,
+        texture: &Image,
+        region_position: Vec2,
+        region_size: Vec2,
+        transform: Transform2D,
+        modulate: Color,
+    ) -> PyResult<()> {
+        // A transform that can't be inverted squashes the quad flat, so it covers no pixels
+        if transform.determinant() == 0.0 {
+            return Ok(());
+        }
+        let inverse = transform.affine_inverse()?;
+        let corners = rect_points(Vec2::ZERO, region_size).map(|corner| transform.xform(corner));
+        let (min, max) = bounds(&corners);
+        let (columns, rows) = self.clip(min, max);
+        for y in rows {
+            for x in columns.clone() {
+                let texel = inverse.xform(Vec2::new(x as f64 + 0.5, y as f64 + 0.5));
+                if texel.x < 0.0
+                    || texel.y < 0.0
+                    || texel.x >= region_size.x
+                    || texel.y >= region_size.y
+                {
+                    continue;
+                }
+                let (u, v) = (
+                    (region_position.x + texel.x).floor(),
+                    (region_position.y + texel.y).floor(),
+                );
+                if u < 0.0 || v < 0.0 || u >= texture.width as f64 || v >= texture.height as f64 {
+                    continue;
+                }
+                let color = texture.pixels[v as usize * texture.width + u as usize];
+                self.blend_clipped(x, y, modulated(color, modulate));
+            }
+        }
+        Ok(())
+    }
+    // Blends a pixel, ignoring ones outside of the framebuffer
+    pub fn blend_clipped(&mut self, x: i64, y: i64, color: Color) {
+        if let Some(index) = self.offset(x, y) {
+            self.pixels[index] = self.pixels[index].blend(color);
+        }
+    }
+    // Bresenham's line, covering exactly the pixels the endpoints fall in
+    fn line(&mut self, start: Vec2, end: Vec2, color: Color) {
+        // Only the part on screen is walked, so lines to far away points still end quickly
+        let size = Vec2::new(self.width as f64, self.height as f64);
+        let Some((start, end)) = clip_line(start, end, size) else {
+            return;
+        };
+        let (mut x, mut y) = (start.x.floor() as i64, start.y.floor() as i64);
+        let (x1, y1) = (end.x.floor() as i64, end.y.floor() as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (step_x, step_y) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut error = dx + dy;
+        loop {
+            self.blend_clipped(x, y, color);
+            if x == x1 && y == y1 {
+                return;
+            }
+            if error * 2 >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if error * 2 <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+    // Fills every pixel whose center is inside the polygon, using the even-odd rule
+    pub fn fill_polygon(&mut self, points: &[Vec2], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        let (min, max) = bounds(points);
+        let rows = (min.y - 0.5).ceil().max(0.0) as i64
+            ..((max.y - 0.5).ceil() as i64).min(self.height as i64);
+        let mut crossings = Vec::new();
+        for y in rows {
+            let center = y as f64 + 0.5;
+            crossings.clear();
+            for (index, start) in points.iter().enumerate() {
+                let end = points[(index + 1) % points.len()];
+                if (start.y <= center) != (end.y <= center) {
+                    crossings
+                        .push(start.x + (center - start.y) * (end.x - start.x) / (end.y - start.y));
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                let start = ((span[0] - 0.5).ceil() as i64).max(0);
+                let end = ((span[1] - 0.5).ceil() as i64).min(self.width as i64);
+                for x in start..end {
+                    self.blend_clipped(x, y, color);
+                }
+            }
+        }
+    }
+}
+
+pub fn modulated(color: Color, modulate: Color) -> Color {
+    let channel = |a: u8, b: u8| ((a as u32 * b as u32 + 127) / 255) as u8;
+    Color {
+        r: channel(color.r, modulate.r),
+        g: channel(color.g, modulate.g),
+        b: channel(color.b, modulate.b),
+        a: channel(color.a, modulate.a),
+    }
+}
+
+pub fn rect_points(min: Vec2, max: Vec2) -> [Vec2; 4] {
+    [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+}
+
+pub fn bounds(points: &[Vec2]) -> (Vec2, Vec2) {
+    points.iter().fold(
+        (
+            Vec2::new(f64::INFINITY, f64::INFINITY),
+            Vec2::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(min, max), point| (min.min(point), max.max(point)),
+    )
+}
+
+// The part of the line inside the rect from the origin to `size`, using Liang-Barsky clipping
+pub fn clip_line(start: Vec2, end: Vec2, size: Vec2) -> Option<(Vec2, Vec2)> {
+    if ![start.x, start.y, end.x, end.y]
+        .iter()
+        .all(|value| value.is_finite())
+    {
+        return None;
+    }
+    let delta = end - start;
+    let (mut enter, mut exit) = (0.0_f64, 1.0_f64);
+    for (direction, distance) in [
+        (-delta.x, start.x),
+        (delta.x, size.x - start.x),
+        (-delta.y, start.y),
+        (delta.y, size.y - start.y),
+    ] {
+        if direction == 0.0 {
+            // Parallel to this edge, so it's either entirely inside or outside of it
+            if distance < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = distance / direction;
+        if direction < 0.0 {
+            enter = enter.max(t);
+        } else {
+            exit = exit.min(t);
+        }
+        if enter > exit {
+            return None;
+        }
+    }
+    Some((start + delta * enter, start + delta * exit))
+}
+
+// The quad covered by a line of the given width
+pub fn thick_line(start: Vec2, end: Vec2, width: f64) -> [Vec2; 4] {
+    let direction = (end - start).normalised();
+    let offset = Vec2::new(-direction.y, direction.x) * (width / 2.0);
+    [start + offset, end + offset, end - offset, start - offset]
+}
@@ -0,0 +1,11 @@
+pub mod canvas;
+pub mod font;
+pub mod framebuffer;
+pub mod renderer;
+pub mod terminal;
+
+pub mod all {
+    pub use super::framebuffer::*;
+    pub use super::renderer::*;
+    pub use super::terminal::*;
+}
@@ -0,0 +1,82 @@
+use pyo3::prelude::*;
+use pyo3::{PyTraverseError, PyVisit};
+
+use super::framebuffer::Framebuffer;
+use crate::builtin_types::all::*;
+use crate::nodetree::node::{as_node, builtin_nodes::all::*};
+
+// Draws the 2D nodes under a node into a framebuffer, parents first so that children end up on top.
+// Set as `Tree.renderer` to draw the whole tree at the end of every frame
+#[pyclass(subclass)]
+#[derive(Clone, Debug)]
+pub struct Renderer {
+    #[pyo3(get)]
+    pub(crate) framebuffer: Py<Framebuffer>,
+    #[pyo3(get, set)]
+    pub(crate) clear_color: Color,
+}
+
+#[pymethods]
+impl Renderer {
+    #[new]
+    #[pyo3(signature = (width, height, clear_color = Color::BLACK))]
+    pub fn new(py: Python, width: usize, height: usize, clear_color: Color) -> PyResult<Self> {
+        Ok(Self {
+            framebuffer: Py::new(py, Framebuffer::new(width, height)?)?,
+            clear_color,
+        })
+    }
+    pub fn render(&self, py: Python, node: &PyCell<Node>) -> PyResult<()> {
+        update_draw_commands(py, node)?;
+        let mut framebuffer = self.framebuffer.try_borrow_mut(py)?;
+        framebuffer.clear(self.clear_color);
+        draw_node(py, node, Transform2D::IDENTITY, &mut framebuffer)
+    }
+    pub fn __traverse__(&self, visit: PyVisit) -> Result<(), PyTraverseError> {
+        visit.call(&self.framebuffer)
+    }
+}
+
+// Runs `_draw` on the visible 2D nodes that queued a redraw, before anything is drawn
+fn update_draw_commands(py: Python, node: &PyCell<Node>) -> PyResult<()> {
+    if let Ok(node2d) = node.downcast::<PyCell<Node2D>>() {
+        let visible = node2d.borrow().visible;
+        if !visible {
+            return Ok(());
+        }
+        Node2D::update_draw_commands(node2d)?;
+    }
+    let children = node.borrow().get_children()?;
+    for child in children {
+        update_draw_commands(py, as_node(py, &child)?)?;
+    }
+    Ok(())
+}
+
+// Hidden 2D nodes hide their children too, and nodes that aren't 2D reset the transform
+fn draw_node(
+    py: Python,
+    node: &PyCell<Node>,
+    parent_transform: Transform2D,
+    target: &mut Framebuffer,
+) -> PyResult<()> {
+    let mut transform = Transform2D::IDENTITY;
+    if let Ok(node2d) = node.downcast::<PyCell<Node2D>>() {
+        let node2d = node2d.borrow();
+        if !node2d.visible {
+            return Ok(());
+        }
+        transform = parent_transform * node2d.get_transform();
+        if let Ok(sprite) = node.downcast::<PyCell<Sprite2D>>() {
+            sprite.borrow().draw(py, target, &transform)?;
+        }
+        for command in &node2d.draw_commands {
+            command.draw(py, target, &transform)?;
+        }
+    }
+    let children = node.borrow().get_children()?;
+    for child in children {
+        draw_node(py, as_node(py, &child)?, transform, target)?;
+    }
+    Ok(())
+}
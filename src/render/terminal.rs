@@ -0,0 +1,208 @@
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use std::fmt::Write;
+
+use super::{framebuffer::Framebuffer, renderer::Renderer};
+use crate::builtin_types::all::*;
+use crate::nodetree::node::Node;
+
+// What a terminal cell shows
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Cell {
+    character: char,
+    foreground: Color,
+    background: Color,
+}
+
+// Draws into the terminal with truecolor escape codes. In half block mode every cell shows two
+// pixels stacked on top of each other, and in text mode every cell is one pixel with an optional
+// character on top. Only the cells that changed since the last frame are written
+#[pyclass(subclass, extends = Renderer)]
+#[derive(Clone, Debug)]
+pub struct TerminalRenderer {
+    #[pyo3(get)]
+    columns: usize,
+    #[pyo3(get)]
+    rows: usize,
+    #[pyo3(get)]
+    mode: u8,
+    // Characters set with `set_cell` and `draw_text`, with their color
+    text: Vec<Option<(char, Color)>>,
+    // What the terminal is showing right now
+    front: Vec<Cell>,
+    full_redraw: bool,
+}
+
+#[pymethods]
+impl TerminalRenderer {
+    #[classattr]
+    pub const MODE_HALF_BLOCK: u8 = 0;
+    #[classattr]
+    pub const MODE_TEXT: u8 = 1;
+
+    #[new]
+    #[pyo3(signature = (columns, rows, mode = Self::MODE_HALF_BLOCK, clear_color = Color::BLACK))]
+    pub fn new(
+        py: Python,
+        columns: usize,
+        rows: usize,
+        mode: u8,
+        clear_color: Color,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let height = match mode {
+            Self::MODE_HALF_BLOCK => rows
+                .checked_mul(2)
+                .ok_or_else(|| PyValueError::new_err("The terminal is too large"))?,
+            Self::MODE_TEXT => rows,
+            _ => return Err(PyValueError::new_err(format!("Unknown mode {mode}"))),
+        };
+        let renderer = Renderer::new(py, columns, height, clear_color)?;
+        let cells = Image::pixel_count(columns, rows)?;
+        Ok(PyClassInitializer::from(renderer).add_subclass(Self {
+            columns,
+            rows,
+            mode,
+            text: vec![None; cells],
+            front: Vec::new(),
+            full_redraw: true,
+        }))
+    }
+    // Renders the node into the framebuffer and shows it in the terminal
+    pub fn render(slf: &PyCell<Self>, py: Python, node: &PyCell<Node>) -> PyResult<()> {
+        // Cloned so that `_draw` callbacks can use the renderer
+        let renderer: Renderer = slf.borrow().as_ref().clone();
+        renderer.render(py, node)?;
+        TerminalRenderer::present(slf, py)
+    }
+    // Writes the changes since the last frame to stdout
+    pub fn present(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        let output = TerminalRenderer::diff(slf, py)?;
+        if !output.is_empty() {
+            let stdout = py.import("sys")?.getattr("stdout")?;
+            stdout.call_method1("write", (output,))?;
+            stdout.call_method0("flush")?;
+        }
+        Ok(())
+    }
+    // The escape codes that bring the terminal from the last frame to the current one,
+    // which then counts as shown
+    pub fn diff(slf: &PyCell<Self>, py: Python) -> PyResult<String> {
+        let mut renderer = slf.borrow_mut();
+        let framebuffer = renderer.as_ref().framebuffer.clone_ref(py);
+        let back = renderer.cells(&*framebuffer.try_borrow(py)?);
+        let mut output = String::new();
+        if renderer.full_redraw {
+            output.push_str("\x1b[0m\x1b[?25l\x1b[2J");
+        }
+        let (mut cursor, mut foreground, mut background) = (None, None, None);
+        for (index, cell) in back.iter().enumerate() {
+            if !renderer.full_redraw && renderer.front[index] == *cell {
+                continue;
+            }
+            let (row, column) = (index / renderer.columns, index % renderer.columns);
+            if cursor != Some((row, column)) {
+                write!(output, "\x1b[{};{}H", row + 1, column + 1).unwrap();
+            }
+            if foreground != Some(cell.foreground) {
+                let Color { r, g, b, .. } = cell.foreground;
+                write!(output, "\x1b[38;2;{r};{g};{b}m").unwrap();
+                foreground = Some(cell.foreground);
+            }
+            if background != Some(cell.background) {
+                let Color { r, g, b, .. } = cell.background;
+                write!(output, "\x1b[48;2;{r};{g};{b}m").unwrap();
+                background = Some(cell.background);
+            }
+            output.push(cell.character);
+            cursor = Some((row, column + 1));
+        }
+        if cursor.is_some() {
+            output.push_str("\x1b[0m");
+        }
+        renderer.front = back;
+        renderer.full_redraw = false;
+        Ok(output)
+    }
+    // Redraws every cell on the next frame, for when something else wrote to the terminal
+    pub fn invalidate(&mut self) {
+        self.full_redraw = true;
+    }
+    // Shows the cursor again below the frame, call it before exiting
+    pub fn restore(&self, py: Python) -> PyResult<()> {
+        let stdout = py.import("sys")?.getattr("stdout")?;
+        stdout.call_method1(
+            "write",
+            (format!("\x1b[0m\x1b[?25h\x1b[{};1H\n", self.rows),),
+        )?;
+        stdout.call_method0("flush")?;
+        Ok(())
+    }
+    // Characters stay until they're replaced or cleared
+    #[pyo3(signature = (column, row, character, color = Color::WHITE))]
+    pub fn set_cell(
+        &mut self,
+        column: usize,
+        row: usize,
+        character: char,
+        color: Color,
+    ) -> PyResult<()> {
+        if column >= self.columns || row >= self.rows {
+            return Err(PyIndexError::new_err(format!(
+                "Cell ({column}, {row}) is outside of the {}x{} terminal",
+                self.columns, self.rows
+            )));
+        }
+        self.text[row * self.columns + column] = Some((character, color));
+        Ok(())
+    }
+    // Writes text to the right of a cell, cutting off what doesn't fit
+    #[pyo3(signature = (column, row, text, color = Color::WHITE))]
+    pub fn draw_text(&mut self, column: usize, row: usize, text: &str, color: Color) {
+        if row >= self.rows {
+            return;
+        }
+        for (offset, character) in text.chars().enumerate() {
+            if column + offset >= self.columns {
+                break;
+            }
+            self.text[row * self.columns + column + offset] = Some((character, color));
+        }
+    }
+    pub fn clear_text(&mut self) {
+        self.text.fill(None);
+    }
+}
+
+impl TerminalRenderer {
+    // The cells for the current framebuffer and text
+    fn cells(&self, framebuffer: &Framebuffer) -> Vec<Cell> {
+        let pixel = |column: usize, row: usize| framebuffer.pixels[row * self.columns + column];
+        let mut cells = Vec::with_capacity(self.columns * self.rows);
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let (top, bottom) = match self.mode {
+                    Self::MODE_HALF_BLOCK => (pixel(column, row * 2), pixel(column, row * 2 + 1)),
+                    _ => (pixel(column, row), pixel(column, row)),
+                };
+                cells.push(match self.text[row * self.columns + column] {
+                    Some((character, color)) => Cell {
+                        character,
+                        foreground: color,
+                        background: top,
+                    },
+                    None if self.mode == Self::MODE_HALF_BLOCK => Cell {
+                        character: '▀',
+                        foreground: top,
+                        background: bottom,
+                    },
+                    None => Cell {
+                        character: ' ',
+                        foreground: top,
+                        background: top,
+                    },
+                });
+            }
+        }
+        cells
+    }
+}
@@ -0,0 +1,68 @@
+from ferrokrait import *
+
+class Enemy(Node2D):
+    def __init__(self, health, speed=1.0):
+        self.health = health
+        self.speed = speed
+
+tree = Tree()
+
+# classes are constructed with the given arguments
+tree.add_node(Enemy, 10, speed=2.0)
+enemy = tree.get_node("Enemy")
+assert enemy.health == 10 and enemy.speed == 2.0
+
+# existing instances are added as they are
+boss = Enemy(100)
+boss.name = "Boss"
+tree.add_node(boss)
+assert tree.get_node("Boss") is boss
+
+for invalid in (int, "Enemy", 5):
+    try:
+        tree.add_node(invalid)
+    except TypeError:
+        pass
+    else:
+        raise AssertionError(f"{invalid!r} was added")
+
+try:
+    tree.add_node(boss, 1)
+except TypeError:
+    pass
+else:
+    raise AssertionError("arguments were accepted with an instance")
+
+# only python subclasses take constructor arguments, ferrokrait's own nodes don't
+for construct in (lambda: Node(5), lambda: Timer(wait_time=2), lambda: Sprite2D(None)):
+    try:
+        construct()
+    except TypeError:
+        pass
+    else:
+        raise AssertionError("a builtin node accepted arguments")
+
+class Boss(Enemy):
+    pass
+
+assert Boss(500).health == 500 and isinstance(Boss(1), Node2D)
+
+# classes that aren't nodes are rejected before they're constructed
+constructed = []
+class NotANode:
+    def __init__(self, *args):
+        constructed.append(args)
+
+# and subclasses without an __init__ don't silently drop their arguments
+class Plain(Node):
+    pass
+
+for node, args in ((NotANode, (1,)), (Plain, (10,))):
+    try:
+        tree.add_node(node, *args)
+    except TypeError:
+        pass
+    else:
+        raise AssertionError(f"{node.__name__} was added")
+assert constructed == [] and isinstance(Plain(), Node)
+print("ok")
@@ -0,0 +1,37 @@
+from ferrokrait import *
+
+log = []
+
+class Enemy(Node):
+    def _ready(self):
+        log.append("enemy")
+
+# By default, every class in the hierarchy has its callback called
+class Chained(Enemy):
+    def _ready(self):
+        log.append("chained")
+
+# Opting out makes callbacks override each other like normal methods
+class Boss(Enemy):
+    __ferrokrait_chain__ = False
+
+    def _ready(self):
+        log.append("boss")
+
+class Miniboss(Boss):
+    def _ready(self):
+        super()._ready()
+        log.append("miniboss")
+
+tree = Tree()
+tree.add_node(Chained).step(1 / 60)
+assert log == ["enemy", "chained"], log
+
+log.clear()
+tree.add_node(Boss)
+assert log == ["boss"], log
+
+log.clear()
+tree.add_node(Miniboss)
+assert log == ["boss", "miniboss"], log
+print("ok")
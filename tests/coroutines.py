@@ -0,0 +1,66 @@
+from ferrokrait import *
+
+log = []
+
+class Enemy(Node):
+    died = Signal(int)
+
+    async def _ready(self):
+        await self.get_tree().next_frame()
+        log.append("enemy frame")
+        self.emit("died", 10)
+
+class Cutscene(Node):
+    async def _ready(self):
+        log.append("start")
+        score = await self.get_node("../Enemy").signal("died")
+        log.append(f"died {score}")
+        await self.get_tree().create_timer(0.5)
+        log.append("timer")
+
+tree = Tree()
+tree.add_node(Enemy).add_node(Cutscene)
+
+tree.step(0.25)
+assert log == ["start", "enemy frame", "died 10"], log
+tree.step_frames(2, 0.25)
+assert log[-1] == "timer", log
+
+# coroutines belonging to a freed node are dropped
+class Looper(Node):
+    async def _ready(self):
+        while True:
+            await self.get_tree().next_frame()
+            log.append("loop")
+
+tree.add_node(Looper)
+looper = tree.get_node("Looper")
+tree.step_frames(2, 0.25)
+looper.queue_free()
+tree.step_frames(2, 0.25)
+# the node is only freed at the end of the next frame
+assert log.count("loop") == 3, log
+
+# an error while closing the coroutine of a freed node doesn't stop the others
+class Stubborn(Node):
+    async def _ready(self):
+        try:
+            await self.get_tree().create_timer(10)
+        finally:
+            raise KeyError("closed")
+
+class Counter(Node):
+    async def _ready(self):
+        while True:
+            await self.get_tree().next_frame()
+            log.append("count")
+
+errors = []
+tree.error_policy = Tree.ERROR_CALLBACK
+tree.on_error = lambda node, error: errors.append((type(node).__name__, type(error)))
+tree.add_node(Stubborn).add_node(Counter)
+tree.get_node("Stubborn").queue_free()
+tree.step_frames(3, 0.25)
+assert errors == [("Stubborn", KeyError)], errors
+assert log.count("count") == 3, log
+print("ok")
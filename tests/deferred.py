@@ -0,0 +1,29 @@
+from ferrokrait import *
+
+log = []
+
+class Spawner(Node):
+    def _process(self, delta):
+        log.append("process")
+        # adding children while the tree is processing is deferred until the callbacks are done
+        self.call_deferred("add_child", Node())
+        self.call_deferred("record", "first")
+        self.get_tree().call_deferred(log.append, "second")
+
+    def record(self, message):
+        log.append(message)
+
+tree = Tree().add_node(Spawner)
+spawner = tree.get_node("Spawner")
+
+tree.step(1 / 60)
+assert log == ["process", "first", "second"], log
+assert len(spawner.get_children()) == 1
+
+# calls deferred while flushing still run before the frame ends
+log.clear()
+spawner.process_mode = Node.PROCESS_MODE_DISABLED
+tree.call_deferred(lambda: tree.call_deferred(log.append, "nested"))
+tree.step(1 / 60)
+assert log == ["nested"], log
+print("ok")
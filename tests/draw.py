@@ -0,0 +1,65 @@
+from ferrokrait import *
+
+RED = Color(255, 0, 0, 255)
+
+def rgba(color):
+    return (color.r, color.g, color.b, color.a)
+
+class Marker(Node2D):
+    def __init__(self):
+        self.draws = 0
+        self.size = Vec2(2, 2)
+
+    def _draw(self):
+        self.draws += 1
+        self.draw_rect(Vec2(0, 0), self.size, RED)
+
+class Label(Node2D):
+    def _draw(self):
+        self.draw_string(Vec2(0, 0), "I", Color.WHITE)
+
+tree = Tree()
+tree.renderer = Renderer(16, 16)
+marker = Marker()
+marker.position = Vec2(4, 4)
+tree.root.add_child(marker)
+
+def pixel(x, y):
+    return rgba(tree.renderer.framebuffer.get_pixel(x, y))
+
+# commands are in local space, and moved by the global transform
+tree.step(1 / 60)
+assert pixel(4, 4) == rgba(RED) and pixel(5, 5) == rgba(RED) and pixel(6, 6) == rgba(Color.BLACK)
+marker.position = Vec2(8, 8)
+marker.scale = Vec2(2, 2)
+tree.step(1 / 60)
+assert pixel(4, 4) == rgba(Color.BLACK) and pixel(11, 11) == rgba(RED)
+
+# _draw only runs again after queue_redraw
+assert marker.draws == 1
+marker.size = Vec2(4, 4)
+tree.step(1 / 60)
+assert marker.draws == 1 and pixel(12, 12) == rgba(Color.BLACK)
+marker.queue_redraw()
+tree.step(1 / 60)
+assert marker.draws == 2 and pixel(12, 12) == rgba(RED)
+
+# hidden nodes aren't drawn
+marker.visible = False
+tree.step(1 / 60)
+assert pixel(11, 11) == rgba(Color.BLACK)
+
+# drawing outside of _draw is an error
+try:
+    marker.draw_line(Vec2(0, 0), Vec2(1, 1), RED)
+    assert False
+except ValueError:
+    pass
+
+# the built in font draws the middle column of an I
+label = Label()
+tree.root.add_child(label)
+tree.step(1 / 60)
+assert all(pixel(2, y) == rgba(Color.WHITE) for y in range(7))
+assert pixel(0, 3) == rgba(Color.BLACK)
+print("ok")
@@ -0,0 +1,62 @@
+from ferrokrait import *
+import gc, weakref
+
+class Typo(Node):
+    def _process(self, delta):
+        self.helth -= 1
+
+class Fine(Node):
+    frames = 0
+    def _process(self, delta):
+        self.frames += 1
+
+tree = Tree().add_node(Typo).add_node(Fine)
+
+tree.error_policy = Tree.ERROR_DISABLE_NODE
+tree.step_frames(3, 1 / 60)
+assert tree.get_node("Typo").process_mode == Node.PROCESS_MODE_DISABLED
+assert tree.get_node("Fine").frames == 3
+
+errors = []
+tree.get_node("Typo").process_mode = Node.PROCESS_MODE_INHERIT
+tree.error_policy = Tree.ERROR_CALLBACK
+tree.on_error = lambda node, error: errors.append((node.get_path(), type(error)))
+tree.step(1 / 60)
+assert errors == [("/root/Typo", AttributeError)]
+
+tree.error_policy = Tree.ERROR_PROPAGATE
+try:
+    tree.step(1 / 60)
+except AttributeError as error:
+    print("propagated:", error)
+
+# errors in signal callbacks from a timer follow the policy too
+class Alarm(Timer):
+    def _ready(self):
+        self.wait_time = 0.01
+        self.connect("timeout", self.on_timeout)
+        self.start()
+    def on_timeout(self):
+        raise RuntimeError("alarm")
+
+tree.error_policy = Tree.ERROR_CALLBACK
+tree.get_node("Typo").queue_free()
+tree.step(1 / 60)
+errors.clear()
+tree.add_node(Alarm)
+tree.step_frames(2, 1 / 60)
+assert errors == [("/root/Alarm", RuntimeError)] * 2, errors
+
+# a callback that refers back to its tree doesn't keep it alive
+class Probe:
+    pass
+
+def leaked():
+    tree, probe = Tree(), Probe()
+    tree.on_error = lambda node, error: (tree, probe)
+    return weakref.ref(probe)
+
+probe = leaked()
+gc.collect()
+assert probe() is None
+print("ok")
@@ -0,0 +1,25 @@
+from ferrokrait import *
+
+class Enemy(Node):
+    health = 10
+    def __init__(self):
+        self.add_to_group("enemies")
+    def hurt(self, amount):
+        self.health -= amount
+
+tree = Tree().add_node(Enemy).add_node(Enemy)
+tree.call_group("enemies", "hurt", 3)
+assert [enemy.health for enemy in tree.get_nodes_in_group("enemies")] == [7, 7]
+tree.set_group("enemies", "health", 1)
+tree.get_node("Enemy").queue_free()
+tree.step(1 / 60)
+assert [enemy.name for enemy in tree.get_nodes_in_group("enemies")] == ["Enemy2"]
+
+# group methods and properties can use the tree themselves
+tree.add_node(Enemy)
+tree.set_group("enemies", "process_mode", Node.PROCESS_MODE_DISABLED)
+assert all(enemy.process_mode == Node.PROCESS_MODE_DISABLED for enemy in tree.get_nodes_in_group("enemies"))
+tree.call_group("enemies", "queue_free")
+tree.step(1 / 60)
+assert tree.get_nodes_in_group("enemies") == []
+print("ok")
@@ -0,0 +1,47 @@
+from ferrokrait import *
+
+log = []
+
+class Weapon(Node):
+    def _ready(self):
+        log.append(("ready", self.name, type(self.get_parent()).__name__))
+
+    def _process(self, delta):
+        log.append(("process", self.name))
+
+class Player(Node):
+    def __init__(self):
+        self.add_child(Weapon())
+
+    # children are readied before their parent
+    def _ready(self):
+        log.append(("ready", self.name, len(self.get_children())))
+
+    # and processed after it, depth first
+    def _process(self, delta):
+        log.append(("process", self.name))
+
+tree = Tree().add_node(Player).add_node(Weapon)
+tree.step(1 / 60)
+assert log == [
+    ("ready", "Weapon", "Player"),
+    ("ready", "Player", 1),
+    ("ready", "Weapon", "Node"),
+    ("process", "Player"),
+    ("process", "Weapon"),
+    ("process", "Weapon"),
+], log
+
+# removed children stop being processed and lose their parent
+player = tree.get_node("Player")
+weapon = player.get_children()[0]
+player.remove_child(weapon)
+assert weapon.get_parent() is None and player.get_children() == []
+log.clear()
+tree.step(1 / 60)
+assert log == [("process", "Player"), ("process", "Weapon")], log
+
+# and can be added somewhere else
+tree.root.add_child(weapon)
+assert weapon.get_parent() is tree.root and len(tree.root.get_children()) == 3
+print("ok")
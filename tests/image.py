@@ -0,0 +1,80 @@
+from ferrokrait import *
+import os, tempfile
+
+RED = Color(255, 0, 0, 255)
+HALF_BLUE = Color(r=0, g=0, b=255, a=128)
+
+def rgba(color):
+    return (color.r, color.g, color.b, color.a)
+
+image = Image(4, 2, Color.BLACK)
+image.set_pixel(0, 0, RED)
+assert rgba(image.get_pixel(0, 0)) == rgba(RED) and rgba(image.get_pixel(1, 0)) == rgba(Color.BLACK)
+try:
+    image.get_pixel(4, 0)
+    assert False
+except IndexError:
+    pass
+
+# blitting blends by alpha and cuts off what doesn't fit
+overlay = Image(2, 2, HALF_BLUE)
+image.blit(overlay, 3, 1)
+assert rgba(image.get_pixel(3, 1)) == (0, 0, 128, 255) and rgba(image.get_pixel(3, 0)) == rgba(Color.BLACK)
+
+flipped = image.copy()
+flipped.flip_x()
+flipped.flip_y()
+assert rgba(flipped.get_pixel(3, 1)) == rgba(RED) and rgba(image.get_pixel(0, 0)) == rgba(RED)
+
+cropped = image.copy()
+cropped.crop(0, 0, 1, 1)
+assert (cropped.width, cropped.height) == (1, 1) and rgba(cropped.get_pixel(0, 0)) == rgba(RED)
+
+# nearest keeps hard edges, bilinear mixes neighbouring pixels
+gradient = Image(2, 1, Color.BLACK)
+gradient.set_pixel(1, 0, Color.WHITE)
+nearest = gradient.copy()
+nearest.resize(4, 1, Image.INTERPOLATE_NEAREST)
+assert [nearest.get_pixel(x, 0).r for x in range(4)] == [0, 0, 255, 255]
+smooth = gradient.copy()
+smooth.resize(4, 1)
+assert [smooth.get_pixel(x, 0).r for x in range(4)] == [0, 64, 191, 255]
+
+# every format reads back what was written, PPM without alpha
+image.set_pixel(1, 1, HALF_BLUE)
+with tempfile.TemporaryDirectory() as directory:
+    for extension in ["png", "bmp", "ppm"]:
+        path = os.path.join(directory, "image." + extension)
+        getattr(image, "save_" + extension)(path)
+        loaded = Image.load(path)
+        assert (loaded.width, loaded.height) == (4, 2)
+        if extension == "ppm":
+            assert rgba(loaded.get_pixel(1, 1)) == (0, 0, 255, 255)
+        else:
+            assert loaded.to_bytes() == image.to_bytes(), extension
+    text = os.path.join(directory, "text.ppm")
+    with open(text, "w") as file:
+        file.write("P3\n# a comment\n1 1\n15\n15 0 15\n")
+    assert rgba(Image.load(text).get_pixel(0, 0)) == (255, 0, 255, 255)
+
+    # sizes too large to allocate are rejected rather than overflowing
+    huge = os.path.join(directory, "huge.ppm")
+    with open(huge, "wb") as file:
+        file.write(b"P6\n4294967296 4294967296\n255\n\0\0\0")
+    for make in (lambda: Image.load(huge), lambda: Image(2 ** 40, 2 ** 40), lambda: image.resize(2 ** 40, 2 ** 40)):
+        try:
+            make()
+        except ValueError as error:
+            assert "too large" in str(error), error
+        else:
+            raise AssertionError("a huge image was created")
+
+# regions past the end of the address space are rejected too
+for x, y in ((2 ** 64 - 1, 0), (0, 2 ** 64 - 1)):
+    try:
+        image.crop(x, y, 1, 1)
+        assert False
+    except ValueError:
+        pass
+image.blit(Image(1, 1, RED), 2 ** 63 - 1, 2 ** 63 - 1)
+print("ok")
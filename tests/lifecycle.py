@@ -0,0 +1,49 @@
+from ferrokrait import *
+
+log = []
+
+class Tracked(Node):
+    def _enter_tree(self):
+        log.append(f"enter {self.name}")
+
+    def _ready(self):
+        log.append(f"ready {self.name}")
+
+    def _exit_tree(self):
+        log.append(f"exit {self.name}")
+
+    def _notification(self, what):
+        if what == Node.NOTIFICATION_PREDELETE:
+            log.append(f"predelete {self.name}")
+        elif what == Node.NOTIFICATION_PAUSED:
+            log.append(f"paused {self.name}")
+        elif what == Node.NOTIFICATION_UNPAUSED:
+            log.append(f"unpaused {self.name}")
+        elif what == Node.NOTIFICATION_WM_FOCUS_OUT:
+            log.append(f"focus out {self.name}")
+
+class Parent(Tracked):
+    def __init__(self):
+        child = Tracked()
+        child.name = "Child"
+        self.add_child(child)
+
+tree = Tree().add_node(Parent)
+tree.step(1 / 60)
+# parents enter before their children, but are ready after them
+assert log == ["enter Parent", "enter Child", "ready Child", "ready Parent"], log
+
+log.clear()
+tree.paused = True
+tree.paused = False
+assert log == ["paused Parent", "paused Child", "unpaused Parent", "unpaused Child"], log
+
+log.clear()
+tree.propagate_notification(Node.NOTIFICATION_WM_FOCUS_OUT)
+assert log == ["focus out Parent", "focus out Child"], log
+
+log.clear()
+tree.get_node("Parent").queue_free()
+tree.step(1 / 60)
+assert log == ["predelete Parent", "predelete Child", "exit Child", "exit Parent"], log
+print("ok")
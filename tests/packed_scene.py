@@ -0,0 +1,54 @@
+from ferrokrait import *
+import json, os, tempfile
+
+class Weapon(Node2D):
+    __export__ = ("damage",)
+    damage = 1
+
+class Player(Node2D):
+    __export__ = ("health",)
+    health = 100
+
+    def __init__(self):
+        self.add_child(Weapon())
+
+player = Player()
+player.name = "Player"
+player.health = 42
+player.position = Vec2(3, 4)
+player.get_node("Weapon").damage = 7
+player.add_to_group("players")
+
+def tampered(change):
+    path = os.path.join(directory, "tampered.json")
+    with open(path, "w") as file:
+        data = json.loads(saved)
+        change(data["root"])
+        json.dump(data, file)
+    try:
+        PackedScene.load(path).instantiate()
+    except (TypeError, ValueError):
+        return
+    assert False, "the tampered scene was loaded"
+
+with tempfile.TemporaryDirectory() as directory:
+    path = os.path.join(directory, "player.json")
+    PackedScene.pack(player).save(path)
+    scene = PackedScene.load(path)
+    with open(path) as file:
+        saved = file.read()
+
+    # scenes can only create ferrokrait's nodes and node classes that have been defined
+    tampered(lambda root: root.update(type="os:system"))
+    tampered(lambda root: root.update(type="ferrokrait:Vec2"))
+    tampered(lambda root: root["properties"].update(modulate={"__type__": "Color", "value": [300, 0, 0, 255]}))
+
+tree = get_tree()
+for _ in range(2):
+    tree.root.add_child(scene.instantiate())
+
+for copy in tree.get_nodes_in_group("players"):
+    assert copy.health == 42 and copy.position.x == 3
+    # the weapon created by Player.__init__ is reused rather than duplicated
+    assert len(copy.get_children()) == 1 and copy.get_node("Weapon").damage == 7
+print([node.name for node in tree.root.get_children()])
@@ -0,0 +1,33 @@
+from ferrokrait import *
+
+class Weapon(Node):
+    pass
+
+class Player(Node):
+    def __init__(self):
+        self.add_child(Weapon())
+        self.add_child(Weapon())
+
+tree = Tree().add_node(Player)
+player = tree.get_node("Player")
+
+# names are generated from the class name and kept unique among siblings
+assert [child.name for child in player.get_children()] == ["Weapon", "Weapon2"]
+player.get_node("Weapon2").name = "Weapon"
+assert [child.name for child in player.get_children()] == ["Weapon", "Weapon2"]
+
+# paths can be relative, go up with .., or start at the root
+assert player.get_node("Weapon2").get_path() == "/root/Player/Weapon2"
+assert player.get_node("Weapon/..") is player
+assert player.get_node("/root/Player/Weapon") is player.get_children()[0]
+assert player.get_node_or_none("Shield") is None
+try:
+    player.get_node("Shield")
+    assert False
+except LookupError:
+    pass
+
+# find_node matches names with wildcards
+assert tree.find_node("Wea*2").get_path() == "/root/Player/Weapon2"
+assert tree.find_node("Pla?er") is player and tree.find_node("Shield*") is None
+print("ok")
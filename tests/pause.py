@@ -0,0 +1,23 @@
+from ferrokrait import *
+
+class Player(Node):
+    moved = 0
+    def _process(self, delta):
+        self.moved += 1
+
+class PauseMenu(Node):
+    shown = 0
+    def __init__(self):
+        self.process_mode = Node.PROCESS_MODE_WHEN_PAUSED
+    def _process(self, delta):
+        self.shown += 1
+
+tree = Tree().add_node(Player).add_node(PauseMenu)
+player, menu = tree.get_node("Player"), tree.get_node("PauseMenu")
+
+tree.step_frames(3, 1 / 60)
+tree.paused = True
+tree.step_frames(2, 1 / 60)
+assert (player.moved, menu.shown) == (3, 2)
+assert not player.can_process() and menu.can_process()
+print("ok")
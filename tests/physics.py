@@ -0,0 +1,32 @@
+from ferrokrait import *
+
+class Body(Node):
+    def _ready(self):
+        self.ticks = []
+
+    def _physics_process(self, delta):
+        self.ticks.append(delta)
+
+tree = Tree().add_node(Body)
+body = tree.get_node("Body")
+tree.physics_ticks_per_second = 4
+
+# physics runs at a fixed rate, carrying the leftover time into the next frame
+tree.step(0.375)
+assert body.ticks == [0.25] and tree.physics_interpolation_fraction == 0.5
+tree.step(0.375)
+assert body.ticks == [0.25] * 3 and tree.physics_interpolation_fraction == 0.0
+
+# a long frame runs at most max_physics_steps_per_frame ticks, and the backlog is dropped
+tree.max_physics_steps_per_frame = 3
+tree.step(10)
+assert len(body.ticks) == 6 and tree.physics_interpolation_fraction == 0.0
+tree.step(0.125)
+assert len(body.ticks) == 6 and tree.physics_interpolation_fraction == 0.5
+
+try:
+    tree.physics_ticks_per_second = 0
+    assert False
+except ValueError:
+    pass
+print("ok")
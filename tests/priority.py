@@ -0,0 +1,24 @@
+from ferrokrait import *
+
+order = []
+
+class Camera(Node):
+    def __init__(self):
+        self.process_priority = 10
+    def _process(self, delta):
+        order.append("camera")
+
+class Player(Node):
+    def _process(self, delta):
+        order.append("player")
+
+class InputHandler(Node):
+    def __init__(self):
+        self.process_priority = -10
+    def _process(self, delta):
+        order.append("input")
+
+tree = Tree().add_node(Camera).add_node(Player).add_node(InputHandler)
+tree.step(1 / 60)
+assert order == ["input", "player", "camera"], order
+print("ok")
@@ -0,0 +1,47 @@
+from ferrokrait import *
+
+log = []
+
+class Bullet(Node):
+    lifetime = 0.5
+
+    def _process(self, delta):
+        self.lifetime -= delta
+        if self.lifetime <= 0:
+            self.queue_free()
+
+    def _exit_tree(self):
+        log.append(("exit", self.name))
+
+class Gun(Node):
+    def __init__(self):
+        self.bullet = Bullet()
+        self.add_child(self.bullet)
+
+tree = Tree().add_node(Gun)
+gun = tree.get_node("Gun")
+bullet = gun.bullet
+
+# queued nodes stay usable until the end of the frame
+tree.step(0.25)
+assert log == [] and is_instance_valid(bullet)
+tree.step(0.25)
+assert log == [("exit", "Bullet")] and not is_instance_valid(bullet)
+assert gun.get_children() == []
+
+# freed nodes raise a ReferenceError when they're used
+try:
+    bullet.get_parent()
+    assert False
+except ReferenceError as error:
+    assert "freed" in str(error)
+
+# freeing a node frees its whole subtree
+gun.add_child(Bullet())
+child = gun.get_children()[0]
+gun.queue_free()
+assert gun.is_queued_for_deletion() and child.is_queued_for_deletion()
+tree.step(0.1)
+assert not is_instance_valid(gun) and not is_instance_valid(child)
+assert tree.root.get_children() == []
+print("ok")
@@ -0,0 +1,94 @@
+from ferrokrait import *
+import os, tempfile
+
+RED = Color(255, 0, 0, 255)
+HALF_BLUE = Color(r=0, g=0, b=255, a=128)
+
+def rgba(color):
+    return (color.r, color.g, color.b, color.a)
+
+BLACK, WHITE = rgba(Color.BLACK), rgba(Color.WHITE)
+
+frame = Framebuffer(8, 8)
+frame.clear(Color.BLACK)
+
+# a rect covers every pixel whose center is inside it
+frame.draw_rect(Vec2(1, 1), Vec2(2, 3), RED)
+assert rgba(frame.get_pixel(1, 1)) == rgba(RED) and rgba(frame.get_pixel(2, 3)) == rgba(RED)
+assert rgba(frame.get_pixel(3, 1)) == BLACK and rgba(frame.get_pixel(1, 4)) == BLACK
+
+# translucent colors are blended over what is already there
+frame.draw_rect(Vec2(0, 0), Vec2(8, 8), HALF_BLUE)
+assert rgba(frame.get_pixel(1, 1)) == (127, 0, 128, 255)
+
+frame.clear(Color.BLACK)
+frame.draw_line(Vec2(0, 0), Vec2(7, 7), RED)
+assert all(rgba(frame.get_pixel(i, i)) == rgba(RED) for i in range(8))
+assert rgba(frame.get_pixel(1, 0)) == BLACK
+
+# lines are clipped to the framebuffer, so far away endpoints are fine
+frame.clear(Color.BLACK)
+frame.draw_line(Vec2(-1e12, 2.5), Vec2(1e12, 2.5), RED)
+frame.draw_line(Vec2(4.5, 1e12), Vec2(4.5, -1e12), RED)
+frame.draw_line(Vec2(1e12, 0), Vec2(1e12, 1e12), RED)
+assert all(rgba(frame.get_pixel(x, 2)) == rgba(RED) for x in range(8))
+assert all(rgba(frame.get_pixel(4, y)) == rgba(RED) for y in range(8))
+assert rgba(frame.get_pixel(0, 0)) == BLACK and rgba(frame.get_pixel(7, 7)) == BLACK
+
+frame.clear(Color.BLACK)
+frame.draw_circle(Vec2(4, 4), 2, RED)
+assert rgba(frame.get_pixel(4, 4)) == rgba(RED) and rgba(frame.get_pixel(0, 0)) == BLACK
+
+# textures are drawn through a transform, and multiplied by the modulate color
+texture = Image(2, 2, Color.WHITE)
+frame.clear(Color.BLACK)
+frame.draw_texture_transformed(texture, Transform2D.from_components(0, Vec2(2, 2), Vec2(2, 2)), RED)
+assert rgba(frame.get_pixel(2, 2)) == rgba(RED) and rgba(frame.get_pixel(5, 5)) == rgba(RED) and rgba(frame.get_pixel(6, 6)) == BLACK
+
+# frames can be written out, PPM without the alpha channel
+with tempfile.TemporaryDirectory() as directory:
+    frame.save_png(os.path.join(directory, "frame.png"))
+    frame.save_ppm(os.path.join(directory, "frame.ppm"))
+    with open(os.path.join(directory, "frame.ppm"), "rb") as ppm:
+        assert ppm.read() == b"P6\n8 8\n255\n" + bytes(
+            byte for index, byte in enumerate(frame.to_bytes()) if index % 4 != 3
+        )
+
+# the tree renders itself at the end of every frame
+tree = Tree()
+tree.renderer = Renderer(4, 4, Color.WHITE)
+tree.step(1 / 60)
+assert rgba(tree.renderer.framebuffer.get_pixel(0, 0)) == WHITE
+
+# nodes are drawn at their global transform
+class Square(Node2D):
+    def _draw(self):
+        self.draw_rect(Vec2(0, 0), Vec2(2, 2), RED)
+
+parent = Node2D()
+parent.position = Vec2(1, 0)
+square = Square()
+square.position = Vec2(1, 1)
+parent.add_child(square)
+tree.root.add_child(parent)
+tree.step(1 / 60)
+pixels = tree.renderer.framebuffer
+assert all(rgba(pixels.get_pixel(x, y)) == rgba(RED) for x in (2, 3) for y in (1, 2))
+assert rgba(pixels.get_pixel(1, 1)) == WHITE and rgba(pixels.get_pixel(2, 3)) == WHITE
+
+# textures squashed to nothing by a zero scale cover no pixels
+class Icon(Node2D):
+    def _draw(self):
+        self.draw_texture(texture, Vec2(0, 0), RED)
+
+parent.scale = Vec2(0, 0)
+square.add_child(Icon())
+tree.step(1 / 60)
+assert all(rgba(pixels.get_pixel(x, y)) == WHITE for x in range(4) for y in range(4))
+
+try:
+    Framebuffer(2 ** 33, 2 ** 33)
+    assert False
+except ValueError:
+    pass
+print("ok")
@@ -0,0 +1,74 @@
+from ferrokrait import *
+
+log = []
+
+class Enemy(Node):
+    died = Signal(str)
+
+    def _ready(self):
+        # nodes can listen to their own signals
+        self.connect("died", self.on_died)
+
+    def on_died(self, name):
+        log.append(("self", name))
+
+class Score(Node):
+    def on_enemy_died(self, name):
+        log.append(("score", name))
+
+tree = Tree().add_node(Enemy).add_node(Score)
+tree.step(1 / 60)
+enemy, score = tree.get_node("Enemy"), tree.get_node("Score")
+enemy.connect("died", score.on_enemy_died)
+enemy.connect("died", lambda name: log.append(("deferred", name)), Node.CONNECT_DEFERRED)
+enemy.connect("died", lambda name: log.append(("once", name)), Node.CONNECT_ONE_SHOT)
+
+# deferred callbacks run once the frame's callbacks are done
+enemy.emit("died", "Enemy")
+assert log == [("self", "Enemy"), ("score", "Enemy"), ("once", "Enemy")], log
+tree.step(1 / 60)
+assert log[-1] == ("deferred", "Enemy"), log
+
+# one-shot connections are dropped after their first call
+log.clear()
+enemy.emit("died", "Enemy")
+tree.step(1 / 60)
+assert ("once", "Enemy") not in log and len(log) == 3, log
+
+# arguments are checked against the declared types
+for args in ((), (5,), ("Enemy", 1)):
+    try:
+        enemy.emit("died", *args)
+    except TypeError:
+        pass
+    else:
+        raise AssertionError(f"{args} was accepted")
+
+# disconnected callables aren't called again
+log.clear()
+enemy.disconnect("died", enemy.on_died)
+assert not enemy.is_connected("died", enemy.on_died)
+enemy.emit("died", "Enemy")
+assert ("self", "Enemy") not in log and ("score", "Enemy") in log, log
+try:
+    enemy.disconnect("died", enemy.on_died)
+    assert False
+except ValueError:
+    pass
+
+# connections to freed nodes are dropped, and freed nodes can't emit
+log.clear()
+score.queue_free()
+tree.step(1 / 60)
+log.clear()
+enemy.emit("died", "Enemy")
+tree.step(1 / 60)
+assert log == [("deferred", "Enemy")], log
+enemy.queue_free()
+tree.step(1 / 60)
+try:
+    enemy.emit("died", "Enemy")
+    assert False
+except ReferenceError:
+    pass
+print("ok")
@@ -0,0 +1,126 @@
+from ferrokrait import *
+
+RED = Color(r=255, g=0, b=0, a=255)
+GREEN = Color(r=0, g=255, b=0, a=255)
+BLUE = Color(r=0, g=0, b=255, a=255)
+WHITE = Color.WHITE
+
+def rgba(color):
+    return (color.r, color.g, color.b, color.a)
+
+# a 2x2 texture with a different color in every corner
+texture = Image(2, 2)
+for (x, y), color in {(0, 0): RED, (1, 0): GREEN, (0, 1): BLUE, (1, 1): WHITE}.items():
+    texture.set_pixel(x, y, color)
+
+tree = Tree()
+tree.renderer = Renderer(8, 8)
+sprite = Sprite2D()
+sprite.texture = texture
+sprite.position = Vec2(4, 4)
+tree.root.add_child(sprite)
+
+def pixel(x, y):
+    return rgba(tree.renderer.framebuffer.get_pixel(x, y))
+
+# centered sprites are drawn around their position
+tree.step(1 / 60)
+assert pixel(3, 3) == rgba(RED) and pixel(4, 4) == rgba(WHITE) and pixel(2, 2) == rgba(Color.BLACK)
+assert sprite.get_rect()[0].x == -1 and sprite.get_rect()[1].x == 2
+
+sprite.flip_h = True
+tree.step(1 / 60)
+assert pixel(3, 3) == rgba(GREEN) and pixel(4, 3) == rgba(RED)
+
+sprite.flip_h = False
+sprite.centered = False
+sprite.offset = Vec2(-4, -4)
+sprite.region_rect = (Vec2(1, 0), Vec2(1, 2))
+sprite.modulate = Color(255, 0, 0, 255)
+tree.step(1 / 60)
+assert pixel(0, 0) == (0, 0, 0, 255) and pixel(0, 1) == rgba(RED) and pixel(1, 0) == rgba(Color.BLACK)
+
+# animations step through the cells of a sprite sheet
+class Player(AnimatedSprite2D):
+    def __init__(self):
+        self.finished = 0
+
+    def _ready(self):
+        self.add_animation_from_grid("idle", texture, 2, 2, fps=10, looping=False)
+        self.add_animation("blink", [Image(1, 1, RED), Image(1, 1, GREEN)], fps=10)
+        self.connect("animation_finished", self.on_finished)
+
+    def on_finished(self):
+        self.finished += 1
+
+sprite.visible = False
+player = tree.add_node(Player).get_node("Player")
+player.centered = False
+player.play("idle")
+assert player.animation == "idle" and player.frame == 0 and player.is_playing()
+tree.step_frames(2, 0.1)
+assert player.frame == 2 and pixel(0, 0) == rgba(BLUE)
+tree.step_frames(3, 0.1)
+assert player.frame == 3 and not player.is_playing() and player.finished == 1
+
+# looping animations wrap around, and stop goes back to the first frame
+player.play("blink")
+tree.step_frames(3, 0.1)
+assert player.frame == 1 and player.finished == 1
+player.pause()
+tree.step_frames(3, 0.1)
+assert player.frame == 1
+player.stop()
+assert player.frame == 0 and player.get_animation_names() == ["blink", "idle"]
+try:
+    player.play("run")
+    assert False
+except ValueError:
+    pass
+
+# huge steps skip whole loops at once instead of counting through them
+player.speed_scale = 1e300
+player.play("blink")
+tree.step(0.1)
+assert player.frame in (0, 1) and player.is_playing()
+player.speed_scale = 1
+
+for invalid in (
+    lambda: player.add_animation("bad", [texture], fps=-1),
+    lambda: player.add_animation("bad", [texture], fps=float("nan")),
+    lambda: setattr(player, "speed_scale", float("inf")),
+    lambda: player.add_animation_from_grid("bad", texture, 3, 1),
+):
+    try:
+        invalid()
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("an invalid animation setting was accepted")
+assert not player.has_animation("bad") and player.speed_scale == 1
+
+# removing the current animation stops it and clears the texture
+player.remove_animation("blink")
+assert player.animation == "" and not player.is_playing() and player.texture is None
+tree.step(0.1)
+
+# replacing the current animation starts it over
+player.add_animation("blink", [Image(1, 1, RED), Image(1, 1, GREEN), Image(1, 1, BLUE)], fps=10)
+player.play("blink")
+tree.step_frames(2, 0.1)
+assert player.frame == 2
+player.add_animation("blink", [Image(1, 1, GREEN)], fps=10)
+assert player.frame == 0
+tree.step(0.1)
+assert player.frame == 0 and player.is_playing() and pixel(0, 0) == rgba(GREEN)
+player.remove_animation("blink")
+
+# errors in animation_finished callbacks follow the tree's error policy
+errors = []
+tree.error_policy = Tree.ERROR_CALLBACK
+tree.on_error = lambda node, error: errors.append((node.name, type(error)))
+player.connect("animation_finished", lambda: 1 / 0)
+player.play("idle")
+tree.step_frames(5, 0.1)
+assert errors == [("Player", ZeroDivisionError)] and not player.is_playing()
+print("ok")
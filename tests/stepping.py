@@ -0,0 +1,37 @@
+from ferrokrait import *
+
+class Counter(Node):
+    def _ready(self):
+        self.frames = 0
+        self.ticks = 0
+
+    def _process(self, delta):
+        self.frames += 1
+        if self.frames == 30:
+            get_tree().quit(3)
+
+    def _physics_process(self, delta):
+        self.ticks += 1
+
+tree = Tree().add_node(Counter)
+counter = tree.get_node("Counter")
+
+# stepping never sleeps, so the same deltas always produce the same result
+tree.step_frames(10, 1 / 30)
+assert counter.frames == 10
+assert counter.ticks == 20
+assert tree.frame_count == 10
+
+assert tree.run(max_frames=5) == 0
+assert counter.frames == 15
+
+assert tree.run() == 3
+assert counter.frames == 30
+
+# trees are independent of each other, and get_tree() returns whichever one is active
+other = Tree().add_node(Counter)
+other.step(1 / 30)
+assert other.get_node("Counter").frames == 1 and counter.frames == 30
+assert get_tree() is not tree and get_tree() is not other
+assert counter.get_tree() is tree
+print("ok")
@@ -0,0 +1,55 @@
+from ferrokrait import *
+import io, sys
+
+RED = Color(255, 0, 0, 255)
+
+class Block(Node2D):
+    def _draw(self):
+        self.draw_rect(Vec2(0, 0), Vec2(1, 1), RED)
+
+tree = Tree()
+terminal = TerminalRenderer(2, 1)
+tree.renderer = terminal
+block = Block()
+tree.root.add_child(block)
+
+def frame():
+    output = io.StringIO()
+    stdout, sys.stdout = sys.stdout, output
+    try:
+        tree.step(1 / 60)
+    finally:
+        sys.stdout = stdout
+    return output.getvalue()
+
+# the first frame clears the screen and draws every cell, two pixels per cell
+first = frame()
+assert first.startswith("\x1b[0m\x1b[?25l\x1b[2J\x1b[1;1H")
+assert "\x1b[38;2;255;0;0m\x1b[48;2;0;0;0m▀" in first and first.count("▀") == 2
+
+# nothing is written when nothing changed, and only the changed cells afterwards
+assert frame() == ""
+block.position = Vec2(1, 1)
+changed = frame()
+assert changed.count("▀") == 2
+terminal.draw_text(1, 0, "@")
+assert frame() == "\x1b[1;2H\x1b[38;2;255;255;255m\x1b[48;2;0;0;0m@\x1b[0m"
+
+# text mode shows one pixel per cell
+text = TerminalRenderer(3, 2, TerminalRenderer.MODE_TEXT)
+assert text.framebuffer.width == 3 and text.framebuffer.height == 2
+text.set_cell(0, 0, "#", RED)
+Renderer.render(text, Node())
+assert text.diff().count("#") == 1 and text.diff() == ""
+try:
+    text.set_cell(3, 0, "#")
+    assert False
+except IndexError:
+    pass
+try:
+    TerminalRenderer(1, 2 ** 63)
+    assert False
+except ValueError:
+    pass
+terminal.restore()
+print("ok")
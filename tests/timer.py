@@ -0,0 +1,27 @@
+from ferrokrait import *
+
+class Spawner(Node):
+    def __init__(self):
+        self.spawned = 0
+        self.timer = Timer()
+        self.timer.wait_time = 0.25
+        self.timer.autostart = True
+        self.add_child(self.timer)
+
+    def _ready(self):
+        self.timer.connect("timeout", self.spawn)
+
+    def spawn(self):
+        self.spawned += 1
+
+tree = Tree().add_node(Spawner)
+spawner = tree.get_node("Spawner")
+
+tree.step_frames(64, 1 / 64)
+assert spawner.spawned == 4, spawner.spawned
+
+# pausable timers stop counting down while the tree is paused
+tree.paused = True
+tree.step_frames(64, 1 / 64)
+assert spawner.spawned == 4
+print("ok")
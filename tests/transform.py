@@ -0,0 +1,37 @@
+from ferrokrait import *
+from math import pi, isclose
+
+def close(a, b):
+    return isclose(a.x, b.x, abs_tol=1e-9) and isclose(a.y, b.y, abs_tol=1e-9)
+
+transform = Transform2D.from_components(pi / 2, Vec2(10, 0), Vec2(2, 2))
+assert close(transform.xform(Vec2(1, 0)), Vec2(10, 2))
+assert close(transform.xform_inv(Vec2(10, 2)), Vec2(1, 0))
+assert (transform * transform.affine_inverse()).is_equal_approx(Transform2D.IDENTITY)
+assert isclose(transform.get_rotation(), pi / 2)
+assert close(transform.get_scale(), Vec2(2, 2))
+
+halfway = Transform2D.IDENTITY.interpolate_with(transform, 0.5)
+assert isclose(halfway.get_rotation(), pi / 4)
+assert close(halfway.origin, Vec2(5, 0))
+
+parent = Node2D()
+parent.position = Vec2(100, 0)
+parent.rotation_rad = pi / 2
+child = Node2D()
+child.position = Vec2(10, 0)
+parent.add_child(child)
+
+# global values follow the parent chain
+assert close(child.global_position, Vec2(100, 10))
+assert isclose(child.global_rotation, pi / 2)
+assert close(child.to_global(Vec2(0, 0)), child.global_position)
+assert close(child.to_local(Vec2(100, 10)), Vec2(0, 0))
+
+child.global_position = Vec2(100, 20)
+assert close(child.position, Vec2(20, 0))
+
+child.look_at(Vec2(200, 20))
+assert isclose(child.global_rotation, 0, abs_tol=1e-9)
+assert isclose(child.rotation_rad, -pi / 2)
+print("ok")
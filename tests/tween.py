@@ -0,0 +1,45 @@
+from ferrokrait import *
+
+class Player(Node2D):
+    def __init__(self):
+        self.alpha = 0.0
+        self.events = []
+
+    def _ready(self):
+        tween = self.create_tween()
+        tween.tween_property(self, "position", Vec2(8, 4), 0.5)
+        tween.parallel().tween_property(self, "alpha", 1.0, 0.25).set_trans(Tween.TRANS_QUAD)
+        tween.tween_interval(0.25)
+        tween.tween_callback(lambda: self.events.append("done"))
+        tween.on_finished = lambda: self.events.append("finished")
+
+tree = Tree().add_node(Player)
+player = tree.get_node("Player")
+
+tree.step_frames(16, 1 / 64)
+assert abs(player.position.x - 4) < 1e-9 and abs(player.position.y - 2) < 1e-9
+assert player.alpha == 1.0
+
+tree.step_frames(32, 1 / 64)
+assert player.position.x == 8 and player.events == ["done", "finished"], player.events
+
+# component tweens, delays and loops
+counter = Node2D()
+tree.root.add_child(counter)
+tween = tree.create_tween().set_loops(2)
+tween.tween_property(counter, "position:x", 1.0, 0.25).from_(0.0).set_delay(0.25)
+tree.step_frames(40, 1 / 64)
+assert counter.position.x == 1.0 and tween.get_loops_left() == 1
+tree.step_frames(24, 1 / 64)
+assert not tween.is_valid()
+
+# tweens bound to a node die with it
+bound = counter.create_tween()
+bound.tween_property(counter, "rotation_rad", 1.0, 1.0)
+counter.queue_free()
+tree.step_frames(2, 1 / 64)
+assert not bound.is_valid()
+
+assert Tween.ease(0.5, Tween.TRANS_LINEAR, Tween.EASE_IN) == 0.5
+assert Tween.ease(1.0, Tween.TRANS_QUAD, Tween.EASE_OUT) == 1.0
+print("ok")